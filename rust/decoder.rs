@@ -1,17 +1,21 @@
 use crate::_cbor2::SYS_MAXSIZE;
-use crate::_cbor2::{BREAK_MARKER, UNDEFINED};
+use crate::_cbor2::{BREAK_MARKER, CONTAINER_END, UNDEFINED};
 use crate::_cbor2::{DEFAULT_MAX_DEPTH, DEFAULT_READ_SIZE};
-use crate::types::{BreakMarkerType, CBORSimpleValue, CBORTag, FrozenDict, DECIMAL_TYPE, FRACTION_TYPE, IPV4ADDRESS_TYPE, IPV4INTERFACE_TYPE, IPV4NETWORK_TYPE, IPV6ADDRESS_TYPE, IPV6INTERFACE_TYPE, IPV6NETWORK_TYPE, UUID_TYPE};
-use crate::utils::{CBORDecodeError, create_cbor_error, raise_cbor_error, raise_cbor_error_from, wrap_cbor_error, PyImportable};
+use crate::_cbor2::{MAJOR_DECODERS, SEMANTIC_DECODERS};
+use crate::types::{ArrayStartToken, BreakMarkerType, CBORSimpleValue, CBORTag, FrozenDict, MapStartToken, TagStartToken, DECIMAL_TYPE, FRACTION_TYPE, IPV4ADDRESS_TYPE, IPV4INTERFACE_TYPE, IPV4NETWORK_TYPE, IPV6ADDRESS_TYPE, IPV6INTERFACE_TYPE, IPV6NETWORK_TYPE, UUID_TYPE};
+use crate::utils::{CBORDecodeError, create_cbor_error, emit_cbor_warning, raise_cbor_error, raise_cbor_error_from, raise_cbor_error_with_context, wrap_cbor_error, PyImportable};
 use half::f16;
 use pyo3::exceptions::{PyException, PyLookupError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyComplex, PyDict, PyFrozenSet, PyInt, PyList, PyMapping, PySet, PyString, PyTuple};
+use pyo3::types::{PyBool, PyBytes, PyComplex, PyDict, PyFrozenSet, PyInt, PyList, PyMapping, PyMemoryView, PySet, PySlice, PyString, PyTuple};
 use pyo3::{IntoPyObjectExt, Py, PyAny, intern, pyclass};
+use std::cell::{Cell, RefCell};
 use std::cmp::{max, min};
 use std::mem::{replace, take};
+use std::net::Ipv6Addr;
 
 const VALID_STR_ERRORS: [&str; 3] = ["strict", "ignore", "replace"];
+const VALID_MIME_POLICIES: [&str; 2] = ["compat32", "default"];
 const SEEK_CUR: u8 = 1;
 
 
@@ -20,11 +24,15 @@ static DATE_FROMORDINAL: PyImportable = PyImportable::new("datetime", "date.from
 static DATETIME_FROMISOFORMAT: PyImportable = PyImportable::new("datetime", "datetime.fromisoformat");
 static DATETIME_FROMTIMESTAMP: PyImportable = PyImportable::new("datetime", "datetime.fromtimestamp");
 static EMAIL_PARSER: PyImportable = PyImportable::new("email.parser", "Parser");
+static EMAIL_BYTES_PARSER: PyImportable = PyImportable::new("email.parser", "BytesParser");
+static EMAIL_POLICY_DEFAULT: PyImportable = PyImportable::new("email.policy", "default");
+static ID_FUNC: PyImportable = PyImportable::new("builtins", "id");
 static INT_FROMBYTES: PyImportable = PyImportable::new("builtins", "int.from_bytes");
 static IPADDRESS_FUNC: PyImportable = PyImportable::new("ipaddress", "ip_address");
 static IPNETWORK_FUNC: PyImportable = PyImportable::new("ipaddress", "ip_network");
 static IPINTERFACE_FUNC: PyImportable = PyImportable::new("ipaddress", "ip_interface");
 static RE_COMPILE: PyImportable = PyImportable::new("re", "compile");
+static TIMEDELTA_TYPE: PyImportable = PyImportable::new("datetime", "timedelta");
 static UTC: PyImportable = PyImportable::new("datetime", "timezone.utc");
 
 /// The CBORDecoder class implements a fully featured `CBOR`_ decoder with
@@ -58,9 +66,60 @@ static UTC: PyImportable = PyImportable::new("datetime", "timezone.utc");
 /// :param str_errors:
 ///     determines how to handle unicode decoding errors (see the `Error Handlers`_
 ///     section in the standard library documentation for details)
+/// :param mime_policy:
+///     the :mod:`email.policy` to use when parsing tag 36 (MIME message) values: ``"compat32"``
+///     (the default, for backward compatibility) yields a legacy :class:`~email.message.Message`,
+///     while ``"default"`` uses :data:`email.policy.default` and yields an
+///     :class:`~email.message.EmailMessage` with structured multipart access
 /// :param int read_size: minimum amount of bytes to read at once (if ``fp`` is seekable)
 /// :param int max_depth:
 ///     maximum allowed depth for nested containers
+/// :param record_offsets:
+///     set to ``True`` to have each decoded node's ``(start_offset, length)`` within the
+///     input recorded in :attr:`offsets`, keyed by ``id()`` of the decoded value
+/// :param zero_copy:
+///     set to ``True`` to have definite-length byte strings that are already fully buffered
+///     returned as a :class:`memoryview` over the read buffer instead of a copied
+///     :class:`bytes` object; indefinite-length byte strings and ones that span a file read
+///     are still copied. The returned views are only valid while the decoder's internal
+///     buffer is not overwritten by further reads, so copy them before reusing the decoder
+///     if they need to outlive the current item
+/// :param canonical:
+///     set to ``True`` to require the input to be in deterministic/canonical CBOR form,
+///     raising :exc:`.CBORDecodeValueError` for non-shortest-form lengths or floats,
+///     indefinite-length byte/text strings, arrays or maps, and map keys that aren't in
+///     canonical (bytewise-sorted, duplicate-free) order. The key order and duplicate checks
+///     cover integer, bool, ``None``, byte string and text string keys only; keys of any
+///     other type are not checked
+/// :param warn_on_noncanonical:
+///     set to ``True`` to have the same non-canonical conditions that :attr:`canonical` would
+///     reject -- duplicate map keys, non-shortest-form lengths, and indefinite-length
+///     byte/text strings, arrays or maps -- instead emit a :exc:`.CBORWarning` and keep
+///     decoding. Has no effect when :attr:`canonical` is also set, since that already raises.
+///     Use Python's ``warnings.filterwarnings("error", category=CBORWarning)`` to escalate
+///     these back into a :exc:`.CBORDecodeError`
+/// :param max_array_length:
+///     maximum number of elements a single array's declared length may claim; checked before
+///     any allocation is sized from that length
+/// :param max_map_pairs:
+///     maximum number of key-value pairs a single map's declared length may claim; checked
+///     before any allocation is sized from that length
+/// :param max_total_items:
+///     maximum number of items (of any major type) that may be decoded in total while
+///     producing one top-level value
+/// :param max_bytes:
+///     maximum number of bytes that may be consumed from the input (see :attr:`pos`) over the
+///     lifetime of this decoder. The limit is checked at the start of each data item, nested
+///     ones included, so a byte or text string whose payload crosses it is still read in full
+///     and the error is raised only when another data item follows
+///
+/// All four limits raise :exc:`.CBORDecodeLimitError` (a subclass of :exc:`.CBORDecodeError`)
+/// when exceeded, so callers can distinguish a resource-limit violation from ordinarily
+/// malformed input.
+///
+/// A premature end of stream additionally sets ``.offset`` (the absolute byte position in
+/// the input, matching :attr:`pos`) and, where available, ``.context`` (a short "while
+/// decoding X" breadcrumb, e.g. ``"map key"``) on the raised :exc:`.CBORDecodeError`.
 ///
 /// .. _CBOR: https://cbor.io/
 #[pyclass(module = "cbor2")]
@@ -71,6 +130,7 @@ pub struct CBORDecoder {
     major_decoders: Option<Py<PyMapping>>,
     semantic_decoders: Option<Py<PyMapping>>,
     str_errors: Py<PyString>,
+    mime_policy: Py<PyString>,
     #[pyo3(get)]
     read_size: usize,
     #[pyo3(get)]
@@ -87,9 +147,49 @@ pub struct CBORDecoder {
     stringref_namespace: Option<Vec<Py<PyAny>>>,
     #[pyo3(get, set)]
     immutable: bool,
+    #[pyo3(get)]
+    record_offsets: bool,
+    offsets: Option<Py<PyDict>>,
+    consumed: usize,
+    /// A short "while decoding X" breadcrumb describing what's currently being read,
+    /// attached (along with `consumed`) to any `CBORDecodeError` raised while it's set; see
+    /// `with_context`.
+    context: Option<&'static str>,
+    #[pyo3(get)]
+    zero_copy: bool,
+    #[pyo3(get)]
+    canonical: bool,
+    #[pyo3(get)]
+    warn_on_noncanonical: bool,
+    #[pyo3(get)]
+    max_array_length: Option<usize>,
+    #[pyo3(get)]
+    max_map_pairs: Option<usize>,
+    #[pyo3(get)]
+    max_total_items: Option<usize>,
+    #[pyo3(get)]
+    max_bytes: Option<usize>,
+    total_items: usize,
 }
 
 impl CBORDecoder {
+    /// Fall back to the global `_cbor2.major_decoders`/`_cbor2.semantic_decoders` registries
+    /// (populated by module init and, for semantic tags, extendable at runtime via
+    /// `register_tag_decoder`) when no per-instance mapping was supplied.
+    fn default_major_decoders(py: Python<'_>) -> Option<Py<PyMapping>> {
+        MAJOR_DECODERS
+            .get(py)
+            .and_then(|d| d.bind(py).clone().into_any().cast_into::<PyMapping>().ok())
+            .map(Bound::unbind)
+    }
+
+    fn default_semantic_decoders(py: Python<'_>) -> Option<Py<PyMapping>> {
+        SEMANTIC_DECODERS
+            .get(py)
+            .and_then(|d| d.bind(py).clone().into_any().cast_into::<PyMapping>().ok())
+            .map(Bound::unbind)
+    }
+
     pub fn new_internal(
         py: Python<'_>,
         fp: Option<&Bound<'_, PyAny>>,
@@ -99,8 +199,17 @@ impl CBORDecoder {
         major_decoders: Option<&Bound<'_, PyMapping>>,
         semantic_decoders: Option<&Bound<'_, PyMapping>>,
         str_errors: &str,
+        mime_policy: &str,
         read_size: usize,
         max_depth: usize,
+        record_offsets: bool,
+        zero_copy: bool,
+        canonical: bool,
+        warn_on_noncanonical: bool,
+        max_array_length: Option<usize>,
+        max_map_pairs: Option<usize>,
+        max_total_items: Option<usize>,
+        max_bytes: Option<usize>,
     ) -> PyResult<Self> {
         let available_bytes = if let Some(buffer) = buffer.as_ref() {
             buffer.len()?
@@ -108,15 +217,21 @@ impl CBORDecoder {
             0
         };
         let bound_str_errors = PyString::new(py, str_errors);
+        let bound_mime_policy = PyString::new(py, mime_policy);
         let mut this = Self {
             fp: None,
             tag_hook: None,
             object_hook: None,
             str_errors: bound_str_errors.clone().unbind(),
+            mime_policy: bound_mime_policy.clone().unbind(),
             read_size,
             max_depth,
-            major_decoders: major_decoders.map(|d| d.clone().unbind()),
-            semantic_decoders: semantic_decoders.map(|d| d.clone().unbind()),
+            major_decoders: major_decoders
+                .map(|d| d.clone().unbind())
+                .or_else(|| Self::default_major_decoders(py)),
+            semantic_decoders: semantic_decoders
+                .map(|d| d.clone().unbind())
+                .or_else(|| Self::default_semantic_decoders(py)),
             read_method: None,
             buffer: buffer.map(Bound::unbind),
             read_position: 0,
@@ -127,6 +242,18 @@ impl CBORDecoder {
             shareables: Vec::new(),
             stringref_namespace: None,
             immutable: false,
+            record_offsets,
+            offsets: None,
+            consumed: 0,
+            context: None,
+            zero_copy,
+            canonical,
+            warn_on_noncanonical,
+            max_array_length,
+            max_map_pairs,
+            max_total_items,
+            max_bytes,
+            total_items: 0,
         };
         if let Some(fp) = fp {
             this.set_fp(fp)?
@@ -134,6 +261,7 @@ impl CBORDecoder {
         this.set_tag_hook(tag_hook)?;
         this.set_object_hook(object_hook)?;
         this.set_str_errors(&bound_str_errors)?;
+        this.set_mime_policy(&bound_mime_policy)?;
         Ok(this)
     }
 
@@ -160,7 +288,7 @@ impl CBORDecoder {
         } else {
             0
         };
-        raise_cbor_error(
+        raise_cbor_error_with_context(
             py,
             "CBORDecodeEOF",
             format!(
@@ -168,17 +296,47 @@ impl CBORDecoder {
                  bytes, got {num_read_bytes} instead)"
             )
             .as_str(),
+            Some(self.consumed),
+            self.context,
         )
     }
 
+    /// Ensure at least one byte is buffered, without raising an error if the stream has
+    /// reached a clean EOF (a zero-length read right at the start of an item).
+    ///
+    /// Returns `true` if a byte is available to begin decoding the next item, or `false` if
+    /// the underlying stream is exhausted. Used by [`CBORSequenceIterator`] to tell a clean
+    /// end of a CBOR sequence apart from a partial item read mid-stream (which still
+    /// surfaces as a `CBORDecodeEOF` from the normal decode path).
+    fn try_peek_first_byte(&mut self, py: Python<'_>) -> PyResult<bool> {
+        if self.available_bytes > 0 {
+            return Ok(true);
+        }
+
+        let read_size: usize = if self.fp_is_seekable { self.read_size } else { 1 };
+        let Some(read) = self.read_method.as_ref() else {
+            return Ok(false);
+        };
+        let bytes_from_fp: Bound<PyBytes> = read.bind(py).call1((&read_size,))?.cast_into()?;
+        let num_read_bytes = bytes_from_fp.len()?;
+        if num_read_bytes == 0 {
+            return Ok(false);
+        }
+
+        self.buffer = Some(bytes_from_fp.unbind());
+        self.read_position = 0;
+        self.available_bytes = num_read_bytes;
+        Ok(true)
+    }
+
     fn read_exact<const N: usize>(&mut self, py: Python<'_>) -> PyResult<[u8; N]> {
-        if self.available_bytes == 0 {
+        let result = if self.available_bytes == 0 {
             // No buffer
             let (new_bytes, amount_read) = self.read_from_fp(py, N)?;
             self.read_position = N;
             self.available_bytes = amount_read - N;
             self.buffer = Some(new_bytes.unbind());
-            Ok(self.buffer.as_ref().unwrap().as_bytes(py)[..N].try_into()?)
+            self.buffer.as_ref().unwrap().as_bytes(py)[..N].try_into()?
         } else if self.available_bytes < N {
             // Combine the remnants of the partial buffer with new data read from the file
             let needed_bytes = N - self.available_bytes;
@@ -188,7 +346,7 @@ impl CBORDecoder {
             self.buffer = Some(new_bytes.unbind());
             self.available_bytes = amount_read - needed_bytes;
             self.read_position = needed_bytes;
-            Ok(concatenated_buffer.try_into().unwrap())
+            concatenated_buffer.try_into().unwrap()
         } else {
             // Return a slice from the existing bytes object
             let slice: [u8; N] = self.buffer.as_ref().unwrap().bind(py).as_bytes()
@@ -196,8 +354,10 @@ impl CBORDecoder {
                 .try_into()?;
             self.available_bytes -= N;
             self.read_position += N;
-            Ok(slice)
-        }
+            slice
+        };
+        self.consumed += N;
+        Ok(result)
     }
 
     fn read_major_and_subtype(&mut self, py: Python<'_>) -> PyResult<(u8, u8)> {
@@ -230,8 +390,26 @@ impl CBORDecoder {
         result
     }
 
+    /// Call the given function with a "while decoding X" breadcrumb set, so that any
+    /// `CBORDecodeError` raised from within it (e.g. a premature end of stream) is reported
+    /// with that context; see `create_cbor_error_with_context`.
+    fn with_context<T>(
+        slf: &Bound<'_, Self>,
+        context: &'static str,
+        f: impl FnOnce() -> PyResult<T>,
+    ) -> PyResult<T> {
+        let mut this = slf.borrow_mut();
+        let old_context = this.context.replace(context);
+        drop(this);
+
+        let result = f();
+
+        slf.borrow_mut().context = old_context;
+        result
+    }
+
     fn add_string_to_namespace(&mut self, string: &Bound<PyAny>, length: usize) {
-        // `string` must be either a PyString or PyBytes object
+        // `string` must be a PyString, PyBytes or (in `zero_copy` mode) PyMemoryView object
         if let Some(stringref_namespace) = self.stringref_namespace.as_mut() {
             let is_referenced = match stringref_namespace.len() {
                 0..24 => length >= 3,
@@ -247,6 +425,96 @@ impl CBORDecoder {
     }
 }
 
+/// One open container on the explicit work stack driven by `CBORDecoder::skip_value`:
+/// `Counted` tracks the number of remaining item-skips owed to a definite-length array
+/// (multiplier 1), map (multiplier 2) or semantic tag (always 1); `Indefinite` is only
+/// closed by a matching break marker.
+enum SkipFrame {
+    Counted(usize),
+    Indefinite,
+}
+
+/// Account for one item (scalar or just-closed container) having been skipped, decrementing
+/// and popping `Counted` frames as they run out and cascading that completion into their own
+/// parent frame. Returns `true` once the stack empties, i.e. once the top-level item being
+/// skipped is fully accounted for.
+fn decrement_and_collapse(stack: &mut Vec<SkipFrame>) -> bool {
+    loop {
+        match stack.last_mut() {
+            None => return true,
+            Some(SkipFrame::Indefinite) => return false,
+            Some(SkipFrame::Counted(remaining)) => {
+                *remaining -= 1;
+                if *remaining > 0 {
+                    return false;
+                }
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Minimal CBOR major-type-0/1/2/3 head encoding for a single length/magnitude value, used
+/// only to compare encoded forms of map keys for `decode_map`'s canonical-ordering check.
+fn canonical_length_head(major: u8, length: u64) -> Vec<u8> {
+    let prefix = major << 5;
+    match length {
+        0..24 => vec![prefix | length as u8],
+        24..256 => vec![prefix | 24, length as u8],
+        256..65536 => {
+            let mut out = vec![prefix | 25];
+            out.extend_from_slice(&(length as u16).to_be_bytes());
+            out
+        }
+        65536..4294967296 => {
+            let mut out = vec![prefix | 26];
+            out.extend_from_slice(&(length as u32).to_be_bytes());
+            out
+        }
+        _ => {
+            let mut out = vec![prefix | 27];
+            out.extend_from_slice(&length.to_be_bytes());
+            out
+        }
+    }
+}
+
+/// Recompute the canonical CBOR encoding of a just-decoded map key, for comparing successive
+/// keys' wire-form bytes in `decode_map`'s canonical-ordering check. The decoder streams its
+/// input without retaining the raw bytes it has already consumed, so this works from the
+/// decoded Python value instead -- which is only valid because canonical mode has already
+/// rejected any non-shortest-form encoding the key could have come from. Returns `None` for
+/// any other key type (anything other than integers, bools, `None`, byte strings and text
+/// strings); the order of such keys, and duplicates among them, are not checked.
+fn canonical_key_bytes(key: &Bound<'_, PyAny>) -> PyResult<Option<Vec<u8>>> {
+    if let Ok(value) = key.cast::<PyBool>() {
+        return Ok(Some(vec![0xf4 + value.is_true() as u8]));
+    }
+    if key.is_none() {
+        return Ok(Some(vec![0xf6]));
+    }
+    if let Ok(value) = key.extract::<i128>() {
+        return Ok(Some(if value >= 0 {
+            canonical_length_head(0, value as u64)
+        } else {
+            canonical_length_head(1, (-1 - value) as u64)
+        }));
+    }
+    if let Ok(bytes) = key.cast::<PyBytes>() {
+        let data = bytes.as_bytes();
+        let mut out = canonical_length_head(2, data.len() as u64);
+        out.extend_from_slice(data);
+        return Ok(Some(out));
+    }
+    if let Ok(string) = key.cast::<PyString>() {
+        let data = string.to_string().into_bytes();
+        let mut out = canonical_length_head(3, data.len() as u64);
+        out.extend_from_slice(&data);
+        return Ok(Some(out));
+    }
+    Ok(None)
+}
+
 #[pymethods]
 impl CBORDecoder {
     #[new]
@@ -258,8 +526,17 @@ impl CBORDecoder {
         major_decoders = None,
         semantic_decoders = None,
         str_errors: "str" = "strict",
+        mime_policy: "str" = "compat32",
         read_size: "int" = DEFAULT_READ_SIZE,
         max_depth: "int" = DEFAULT_MAX_DEPTH,
+        record_offsets: "bool" = false,
+        zero_copy: "bool" = false,
+        canonical: "bool" = false,
+        warn_on_noncanonical: "bool" = false,
+        max_array_length: "int | None" = None,
+        max_map_pairs: "int | None" = None,
+        max_total_items: "int | None" = None,
+        max_bytes: "int | None" = None,
     ))]
     pub fn new(
         py: Python<'_>,
@@ -269,8 +546,17 @@ impl CBORDecoder {
         major_decoders: Option<&Bound<'_, PyMapping>>,
         semantic_decoders: Option<&Bound<'_, PyMapping>>,
         str_errors: &str,
+        mime_policy: &str,
         read_size: usize,
         max_depth: usize,
+        record_offsets: bool,
+        zero_copy: bool,
+        canonical: bool,
+        warn_on_noncanonical: bool,
+        max_array_length: Option<usize>,
+        max_map_pairs: Option<usize>,
+        max_total_items: Option<usize>,
+        max_bytes: Option<usize>,
     ) -> PyResult<Self> {
         Self::new_internal(
             py,
@@ -281,11 +567,39 @@ impl CBORDecoder {
             major_decoders,
             semantic_decoders,
             str_errors,
+            mime_policy,
             read_size,
             max_depth,
+            record_offsets,
+            zero_copy,
+            canonical,
+            warn_on_noncanonical,
+            max_array_length,
+            max_map_pairs,
+            max_total_items,
+            max_bytes,
         )
     }
 
+    /// A mapping of ``id(value)`` to ``(start_offset, length)`` for every node produced while
+    /// :attr:`record_offsets` is enabled, recording where in the input each decoded value came
+    /// from. :data:`None` unless :attr:`record_offsets` was set and at least one item has been
+    /// decoded.
+    #[getter]
+    fn offsets(&self, py: Python<'_>) -> Option<Py<PyDict>> {
+        self.offsets.as_ref().map(|offsets| offsets.clone_ref(py))
+    }
+
+    /// The absolute number of bytes pulled from the stream since this decoder was constructed.
+    ///
+    /// Unlike the internal read buffer position, this never resets, making it useful for error
+    /// reporting and for aligning with external framing when decoding multiple items from the
+    /// same stream.
+    #[getter]
+    fn pos(&self) -> usize {
+        self.consumed
+    }
+
     #[getter]
     fn fp(&self, py: Python<'_>) -> Option<Py<PyAny>> {
         self.fp.as_ref().map(|fp| fp.clone_ref(py))
@@ -375,6 +689,23 @@ impl CBORDecoder {
         Ok(())
     }
 
+    #[getter]
+    fn mime_policy(&self, py: Python<'_>) -> PyResult<String> {
+        self.mime_policy.bind(py).extract()
+    }
+
+    #[setter]
+    fn set_mime_policy(&mut self, mime_policy: &Bound<'_, PyString>) -> PyResult<()> {
+        let as_string: &str = mime_policy.extract()?;
+        if !VALID_MIME_POLICIES.contains(&as_string) {
+            return Err(PyValueError::new_err(format!(
+                "invalid mime_policy value: '{mime_policy}'"
+            )));
+        }
+        self.mime_policy = mime_policy.clone().unbind();
+        Ok(())
+    }
+
     /// Read bytes from the data stream.
     ///
     /// :param int amount: the number of bytes to read
@@ -385,14 +716,14 @@ impl CBORDecoder {
             return Ok(Vec::default());
         }
 
-        if self.available_bytes == 0 {
+        let result = if self.available_bytes == 0 {
             // No buffer
             let (new_bytes, amount_read) = self.read_from_fp(py, amount)?;
             self.read_position = amount;
             self.available_bytes = amount_read - amount;
             let new_buffer = new_bytes.as_bytes()[..amount].to_vec();
             self.buffer = Some(new_bytes.unbind());
-            Ok(new_buffer)
+            new_buffer
         } else if self.available_bytes < amount {
             // Combine the remnants of the partial buffer with new data read from the file
             let needed_bytes = amount - self.available_bytes;
@@ -403,7 +734,7 @@ impl CBORDecoder {
             self.buffer = Some(new_bytes.unbind());
             self.available_bytes = amount_read - needed_bytes;
             self.read_position = needed_bytes;
-            Ok(concatenated_buffer)
+            concatenated_buffer
         } else {
             // Return a slice from the existing bytes object
             let vec = self.buffer.as_ref().unwrap().as_bytes(py)
@@ -411,8 +742,28 @@ impl CBORDecoder {
                 .to_vec();
             self.available_bytes -= amount;
             self.read_position += amount;
-            Ok(vec)
-        }
+            vec
+        };
+        self.consumed += amount;
+        Ok(result)
+    }
+
+    /// Return a zero-copy `memoryview` over `amount` bytes already sitting in the buffer at
+    /// the current read position, advancing past them exactly like `read` would.
+    ///
+    /// Only valid when `self.available_bytes >= amount`; unlike `read`, this never reads more
+    /// data from `fp`, so callers must check that first.
+    fn read_view<'py>(&mut self, py: Python<'py>, amount: usize) -> PyResult<Bound<'py, PyAny>> {
+        let buffer = self.buffer.as_ref().unwrap().bind(py);
+        let start = self.read_position;
+        let slice = PySlice::new(py, start as isize, (start + amount) as isize, 1);
+        let view = PyMemoryView::from(buffer.as_any())?
+            .into_any()
+            .get_item(&slice)?;
+        self.available_bytes -= amount;
+        self.read_position += amount;
+        self.consumed += amount;
+        Ok(view)
     }
 
     /// Set the shareable value for the last encountered shared value marker,
@@ -431,6 +782,40 @@ impl CBORDecoder {
     ///
     /// :raises CBORDecodeError: if there is any problem decoding the stream
     pub fn decode<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        if !slf.borrow().record_offsets {
+            return Self::decode_dispatch(slf);
+        }
+
+        let start_offset = slf.borrow().consumed;
+        let value = Self::decode_dispatch(slf)?;
+        let length = slf.borrow().consumed - start_offset;
+        slf.borrow_mut()
+            .record_offset(slf.py(), &value, start_offset, length)?;
+        Ok(value)
+    }
+
+    /// Record the byte span of a just-decoded node into :attr:`offsets`, keyed by
+    /// ``id(value)`` since the decoded value may not be hashable.
+    fn record_offset(
+        &mut self,
+        py: Python<'_>,
+        value: &Bound<'_, PyAny>,
+        start_offset: usize,
+        length: usize,
+    ) -> PyResult<()> {
+        let offsets = match &self.offsets {
+            Some(offsets) => offsets.bind(py).clone(),
+            None => {
+                let offsets = PyDict::new(py);
+                self.offsets = Some(offsets.clone().unbind());
+                offsets
+            }
+        };
+        let key: u64 = ID_FUNC.get(py)?.call1((value,))?.extract()?;
+        offsets.set_item(key, (start_offset, length))
+    }
+
+    fn decode_dispatch<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
         let py = slf.py();
         let mut this = slf.borrow_mut();
         let (major_type, subtype) = this.read_major_and_subtype(py)?;
@@ -447,6 +832,27 @@ impl CBORDecoder {
             );
         }
 
+        if let Some(max_bytes) = this.max_bytes
+            && this.consumed > max_bytes
+        {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeLimitError",
+                format!("maximum input size ({max_bytes} bytes) exceeded").as_str(),
+            );
+        }
+
+        this.total_items += 1;
+        if let Some(max_total_items) = this.max_total_items
+            && this.total_items > max_total_items
+        {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeLimitError",
+                format!("maximum total item count ({max_total_items}) exceeded").as_str(),
+            );
+        }
+
         if let Some(major_decoders) = &this.major_decoders {
             match major_decoders.bind(py).get_item(&major_type) {
                 Ok(decoder) => {
@@ -465,7 +871,7 @@ impl CBORDecoder {
         let result = match major_type {
             0 => this.decode_uint(py, subtype),
             1 => this.decode_negint(py, subtype),
-            2 => this.decode_bytestring(py, subtype)?.into_bound_py_any(py),
+            2 => this.decode_bytestring(py, subtype),
             3 => this.decode_string(py, subtype)?.into_bound_py_any(py),
             4 => {
                 drop(this);
@@ -501,6 +907,7 @@ impl CBORDecoder {
             this.shareables.clear();
             this.stringref_namespace = None;
             this.share_index = None;
+            this.total_items = 0;
 
             // If fp was seekable and excess data has been read, empty the buffer and rewind the
             // file
@@ -533,130 +940,673 @@ impl CBORDecoder {
         })
     }
 
-    /// Wrap the given bytestring as a file and call :meth:`decode` with it as
-    /// the argument.
+    /// Consume the next data item from the stream without decoding it into a Python object.
     ///
-    /// This method was intended to be used from the ``tag_hook`` hook when an
-    /// object needs to be decoded separately from the rest but while still
-    /// taking advantage of the shared value registry.
+    /// This avoids allocating Python objects and running tag/object hooks for items that are
+    /// of no interest, at the cost of not returning anything. Like :meth:`decode`, this
+    /// enforces :attr:`max_depth`, :attr:`max_array_length`, :attr:`max_map_pairs`,
+    /// :attr:`max_total_items` and :attr:`max_bytes`, and resets the shared-value/string-reference
+    /// state once a top-level item has been fully skipped.
     ///
-    /// :param bytes buf: the buffer from which to decode a CBOR object
-    #[pyo3(signature = (buf: "bytes", /))]
-    pub fn decode_from_bytes<'py>(
-        slf: &Bound<'py, Self>,
-        buf: Bound<'py, PyBytes>,
-    ) -> PyResult<Bound<'py, PyAny>> {
+    /// :raises CBORDecodeError: if there is any problem decoding the stream
+    pub fn skip(slf: &Bound<'_, Self>) -> PyResult<()> {
+        let py = slf.py();
         let mut this = slf.borrow_mut();
-        let fp = this.fp.take();
-        let read_position = replace(&mut this.read_position, 0);
-        let available_bytes = replace(&mut this.available_bytes, buf.len()?);
-        let buffer = replace(&mut this.buffer, Some(buf.unbind()));
-        drop(this);
+        this.skip_item(py)?;
 
-        let result = Self::decode(slf);
+        // Clear shareables and string references to prevent any leaks, just like decode() does
+        if this.decode_depth == 0 {
+            this.shareables.clear();
+            this.stringref_namespace = None;
+            this.share_index = None;
+            this.total_items = 0;
 
-        this = slf.borrow_mut();
-        this.fp = fp;
-        this.buffer = buffer;
-        this.read_position = read_position;
-        this.available_bytes = available_bytes;
-        result
+            // If fp was seekable and excess data has been read, empty the buffer and rewind the
+            // file
+            if this.available_bytes > 0
+                && let Some(fp) = &this.fp
+            {
+                let offset = -(this.available_bytes as isize);
+                fp.call_method1(py, intern!(py, "seek"), (offset, SEEK_CUR))?;
+                this.buffer = None;
+                this.available_bytes = 0;
+                this.read_position = 0;
+            }
+        }
+        Ok(())
     }
 
-    //
-    // Decoders for major tags (0-7)
-    //
-
-    /// Decode the length of the next item.
+    /// Advance past the next complete data item, of any type, without materializing any
+    /// Python objects for its contents, and return the number of bytes it occupied in the
+    /// stream.
     ///
-    /// This is a low-level operation that may be needed by custom decoder callbacks.
+    /// Unlike :meth:`skip`, which walks the item through ordinary (bounded, but still
+    /// recursive) Rust function calls, this drives an explicit counter stack -- one frame per
+    /// open array, map or tagged value -- so arbitrarily deeply nested input is handled in
+    /// constant Rust stack space. Useful for indexing large CBOR logs or seeking past a
+    /// record whose value isn't needed. Enforces :attr:`max_depth`, :attr:`max_array_length`,
+    /// :attr:`max_map_pairs`, :attr:`max_total_items` and :attr:`max_bytes`, the same as
+    /// :meth:`skip` and :meth:`decode`.
     ///
-    /// :param int subtype:
-    /// :return: the length of the item, or :data:`None` to indicate an indefinite-length item
-    /// :rtype: int | None
-    fn decode_length(&mut self, py: Python<'_>, subtype: u8) -> PyResult<Option<usize>> {
-        let length = match subtype {
-            ..24 => Some(subtype as usize),
-            24 => Some(self.read_exact::<1>(py)?[0] as usize),
-            25 => Some(u16::from_be_bytes(self.read_exact(py)?) as usize),
-            26 => Some(u32::from_be_bytes(self.read_exact(py)?) as usize),
-            27 => Some(u64::from_be_bytes(self.read_exact(py)?) as usize),
-            31 => None,
-            _ => {
-                let msg = format!("unknown unsigned integer subtype 0x{subtype:x}");
-                raise_cbor_error(py, "CBORDecodeValueError", msg.as_str())?
+    /// :return: the number of bytes consumed from the stream for this item
+    /// :raises CBORDecodeError: if there is any problem decoding the stream
+    pub fn skip_value(slf: &Bound<'_, Self>) -> PyResult<usize> {
+        let py = slf.py();
+        let mut this = slf.borrow_mut();
+        let start = this.consumed;
+        let mut stack: Vec<SkipFrame> = Vec::new();
+        loop {
+            if let Some(max_bytes) = this.max_bytes
+                && this.consumed > max_bytes
+            {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeLimitError",
+                    format!("maximum input size ({max_bytes} bytes) exceeded").as_str(),
+                );
             }
-        };
-        Ok(length)
-    }
-
-    #[pyo3(signature = (subtype: "int"))]
-    fn decode_uint<'py>(&mut self, py: Python<'py>, subtype: u8) -> PyResult<Bound<'py, PyAny>> {
-        // Major tag 0
-        let uint = self.decode_length_finite(py, subtype)?;
-        let py_int = uint.into_bound_py_any(py)?;
-        Ok(py_int)
-    }
 
-    #[pyo3(signature = (subtype: "int"))]
-    fn decode_negint<'py>(&mut self, py: Python<'py>, subtype: u8) -> PyResult<Bound<'py, PyAny>> {
-        // Major tag 1
-        let uint = self.decode_length_finite(py, subtype)?;
-        let signed_int = -(uint as i128) - 1;
-        let py_int = signed_int.into_bound_py_any(py)?;
-        Ok(py_int)
-    }
+            this.total_items += 1;
+            if let Some(max_total_items) = this.max_total_items
+                && this.total_items > max_total_items
+            {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeLimitError",
+                    format!("maximum total item count ({max_total_items}) exceeded").as_str(),
+                );
+            }
 
-    #[pyo3(signature = (subtype: "int"))]
-    fn decode_bytestring<'py>(
-        &mut self,
-        py: Python<'py>,
-        subtype: u8,
-    ) -> PyResult<Bound<'py, PyBytes>> {
-        // Major tag 2
-        let (decoded, length) = match self.decode_length(py, subtype)? {
-            None => {
-                // Indefinite length
-                let mut bytes = PyBytes::new(py, b"");
-                let mut total_length: usize = 0;
-                let sys_maxsize = *SYS_MAXSIZE.get(py).unwrap();
-                loop {
-                    let (major_type, subtype) = self.read_major_and_subtype(py)?;
-                    match (major_type, subtype) {
-                        (2, _) => {
-                            let length = self.decode_length_finite(py, subtype)?;
-                            if length > sys_maxsize {
-                                return raise_cbor_error(
-                                    py,
-                                    "CBORDecodeValueError",
-                                    format!(
-                                        "chunk too long in an indefinite bytestring chunk: {length}"
-                                    )
-                                    .as_str(),
-                                );
-                            }
-                            total_length += length;
-                            let chunk = self.read(py, length)?;
-                            bytes = bytes.add(chunk)?.cast_into()?;
+            let (major_type, subtype) = this.read_major_and_subtype(py)?;
+            match major_type {
+                0 | 1 => {
+                    this.decode_length_finite(py, subtype)?;
+                    if decrement_and_collapse(&mut stack) {
+                        break;
+                    }
+                }
+                2 | 3 => {
+                    this.skip_string_or_bytes(py, major_type, subtype)?;
+                    if decrement_and_collapse(&mut stack) {
+                        break;
+                    }
+                }
+                4 | 5 => match this.decode_length(py, subtype)? {
+                    None => {
+                        if stack.len() == this.max_depth {
+                            return raise_cbor_error(
+                                py,
+                                "CBORDecodeError",
+                                format!(
+                                    "maximum container nesting depth ({}) exceeded",
+                                    this.max_depth
+                                )
+                                .as_str(),
+                            );
                         }
-                        (7, 31) => break (bytes, total_length), // break marker
-                        _ => {
+                        stack.push(SkipFrame::Indefinite);
+                    }
+                    Some(0) => {
+                        if decrement_and_collapse(&mut stack) {
+                            break;
+                        }
+                    }
+                    Some(length) => {
+                        if stack.len() == this.max_depth {
                             return raise_cbor_error(
                                 py,
-                                "CBORDecodeValueError",
+                                "CBORDecodeError",
                                 format!(
-                                    "non-byte string (major type {major_type}) found in indefinite \
-                                    length byte string"
+                                    "maximum container nesting depth ({}) exceeded",
+                                    this.max_depth
+                                )
+                                .as_str(),
+                            );
+                        }
+                        if major_type == 4
+                            && let Some(max_array_length) = this.max_array_length
+                            && length > max_array_length
+                        {
+                            return raise_cbor_error(
+                                py,
+                                "CBORDecodeLimitError",
+                                format!(
+                                    "declared array length ({length}) exceeds the configured \
+                                     maximum ({max_array_length})"
+                                )
+                                .as_str(),
+                            );
+                        } else if major_type == 5
+                            && let Some(max_map_pairs) = this.max_map_pairs
+                            && length > max_map_pairs
+                        {
+                            return raise_cbor_error(
+                                py,
+                                "CBORDecodeLimitError",
+                                format!(
+                                    "declared map pair count ({length}) exceeds the configured \
+                                     maximum ({max_map_pairs})"
                                 )
                                 .as_str(),
                             );
                         }
+                        let item_multiplier = if major_type == 5 { 2 } else { 1 };
+                        stack.push(SkipFrame::Counted(length * item_multiplier));
                     }
+                },
+                6 => {
+                    this.decode_length_finite(py, subtype)?;
+                    if stack.len() == this.max_depth {
+                        return raise_cbor_error(
+                            py,
+                            "CBORDecodeError",
+                            format!(
+                                "maximum container nesting depth ({}) exceeded",
+                                this.max_depth
+                            )
+                            .as_str(),
+                        );
+                    }
+                    stack.push(SkipFrame::Counted(1));
                 }
-            }
-            Some(length) if length <= 65536 => {
+                7 if subtype == 31 => {
+                    match stack.pop() {
+                        Some(SkipFrame::Indefinite) => {}
+                        _ => {
+                            return raise_cbor_error(
+                                py,
+                                "CBORDecodeValueError",
+                                "break marker found outside an indefinite-length item",
+                            );
+                        }
+                    }
+                    if decrement_and_collapse(&mut stack) {
+                        break;
+                    }
+                }
+                7 => {
+                    this.skip_special(py, subtype)?;
+                    if decrement_and_collapse(&mut stack) {
+                        break;
+                    }
+                }
+                _ => {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeError",
+                        format!("invalid major type: {major_type}").as_str(),
+                    );
+                }
+            }
+        }
+
+        // Clear shareables and string references to prevent any leaks, just like skip() does
+        if this.decode_depth == 0 {
+            this.shareables.clear();
+            this.stringref_namespace = None;
+            this.share_index = None;
+            this.total_items = 0;
+
+            // If fp was seekable and excess data has been read, empty the buffer and rewind the
+            // file
+            if this.available_bytes > 0
+                && let Some(fp) = &this.fp
+            {
+                let offset = -(this.available_bytes as isize);
+                fp.call_method1(py, intern!(py, "seek"), (offset, SEEK_CUR))?;
+                this.buffer = None;
+                this.available_bytes = 0;
+                this.read_position = 0;
+            }
+        }
+
+        Ok(this.consumed - start)
+    }
+
+    /// Return the major type (0-7) of the next item in the stream without consuming it.
+    ///
+    /// Returns ``None`` if the underlying stream has reached a clean end-of-file. This lets
+    /// custom ``major_decoders``/``tag_hook`` callbacks branch on the upcoming type before
+    /// deciding how to proceed, without having to call :meth:`decode` speculatively.
+    ///
+    /// :raises CBORDecodeError: if there is any problem reading from the stream
+    pub fn peek_type(slf: &Bound<'_, Self>) -> PyResult<Option<u8>> {
+        let py = slf.py();
+        let mut this = slf.borrow_mut();
+        if !this.try_peek_first_byte(py)? {
+            return Ok(None);
+        }
+        let byte = this.buffer.as_ref().unwrap().bind(py).as_bytes()[this.read_position];
+        Ok(Some(byte >> 5))
+    }
+
+    fn skip_item(&mut self, py: Python<'_>) -> PyResult<()> {
+        let (major_type, subtype) = self.read_major_and_subtype(py)?;
+        self.skip_after_header(py, major_type, subtype)
+    }
+
+    fn skip_after_header(&mut self, py: Python<'_>, major_type: u8, subtype: u8) -> PyResult<()> {
+        if self.decode_depth == self.max_depth {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeError",
+                format!(
+                    "maximum container nesting depth ({}) exceeded",
+                    self.max_depth
+                )
+                .as_str(),
+            );
+        }
+
+        if let Some(max_bytes) = self.max_bytes
+            && self.consumed > max_bytes
+        {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeLimitError",
+                format!("maximum input size ({max_bytes} bytes) exceeded").as_str(),
+            );
+        }
+
+        self.total_items += 1;
+        if let Some(max_total_items) = self.max_total_items
+            && self.total_items > max_total_items
+        {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeLimitError",
+                format!("maximum total item count ({max_total_items}) exceeded").as_str(),
+            );
+        }
+
+        self.decode_depth += 1;
+        let result = match major_type {
+            0 | 1 => self.decode_length_finite(py, subtype).map(|_| ()),
+            2 | 3 => self.skip_string_or_bytes(py, major_type, subtype),
+            4 => self.skip_sequence(py, subtype, 1),
+            5 => self.skip_sequence(py, subtype, 2),
+            6 => {
+                self.decode_length_finite(py, subtype)?;
+                self.skip_item(py)
+            }
+            7 => self.skip_special(py, subtype),
+            _ => raise_cbor_error(
+                py,
+                "CBORDecodeError",
+                format!("invalid major type: {major_type}").as_str(),
+            ),
+        };
+        self.decode_depth -= 1;
+        result
+    }
+
+    /// Discard the contents of a (possibly chunked, indefinite-length) byte or text string,
+    /// major types 2 and 3.
+    fn skip_string_or_bytes(
+        &mut self,
+        py: Python<'_>,
+        major_type: u8,
+        subtype: u8,
+    ) -> PyResult<()> {
+        match self.decode_length(py, subtype)? {
+            None => {
+                let sys_maxsize = *SYS_MAXSIZE.get(py).unwrap();
+                loop {
+                    let (chunk_major, chunk_subtype) = self.read_major_and_subtype(py)?;
+                    if chunk_major == 7 && chunk_subtype == 31 {
+                        return Ok(());
+                    } else if chunk_major != major_type {
+                        return raise_cbor_error(
+                            py,
+                            "CBORDecodeValueError",
+                            format!(
+                                "unexpected major type {chunk_major} in indefinite length chunk"
+                            )
+                            .as_str(),
+                        );
+                    }
+                    let length = self.decode_length_finite(py, chunk_subtype)?;
+                    if length > sys_maxsize {
+                        return raise_cbor_error(
+                            py,
+                            "CBORDecodeValueError",
+                            format!("chunk too long in an indefinite length chunk: {length}")
+                                .as_str(),
+                        );
+                    }
+                    self.read(py, length)?;
+                }
+            }
+            Some(length) => {
+                self.read(py, length)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Discard the items of an array (major type 4, `item_multiplier` 1) or the key/value
+    /// pairs of a map (major type 5, `item_multiplier` 2), definite or indefinite length.
+    fn skip_sequence(&mut self, py: Python<'_>, subtype: u8, item_multiplier: usize) -> PyResult<()> {
+        match self.decode_length(py, subtype)? {
+            None => loop {
+                let (major_type, subtype) = self.read_major_and_subtype(py)?;
+                if major_type == 7 && subtype == 31 {
+                    return Ok(());
+                }
+                self.skip_after_header(py, major_type, subtype)?;
+            },
+            Some(count) => {
+                if item_multiplier == 1
+                    && let Some(max_array_length) = self.max_array_length
+                    && count > max_array_length
+                {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeLimitError",
+                        format!(
+                            "declared array length ({count}) exceeds the configured maximum \
+                             ({max_array_length})"
+                        )
+                        .as_str(),
+                    );
+                } else if item_multiplier == 2
+                    && let Some(max_map_pairs) = self.max_map_pairs
+                    && count > max_map_pairs
+                {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeLimitError",
+                        format!(
+                            "declared map pair count ({count}) exceeds the configured \
+                             maximum ({max_map_pairs})"
+                        )
+                        .as_str(),
+                    );
+                }
+                for _ in 0..count * item_multiplier {
+                    self.skip_item(py)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Discard a major type 7 (simple value/float/break) item, consuming the extra bytes
+    /// implied by its subtype.
+    fn skip_special(&mut self, py: Python<'_>, subtype: u8) -> PyResult<()> {
+        match subtype {
+            0..24 | 31 => Ok(()),
+            24 => {
+                self.read_exact::<1>(py)?;
+                Ok(())
+            }
+            25 => {
+                self.read_exact::<2>(py)?;
+                Ok(())
+            }
+            26 => {
+                self.read_exact::<4>(py)?;
+                Ok(())
+            }
+            27 => {
+                self.read_exact::<8>(py)?;
+                Ok(())
+            }
+            _ => raise_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                format!("undefined reserved major type 7 subtype 0x{subtype:x}").as_str(),
+            ),
+        }
+    }
+
+    /// Wrap the given bytestring as a file and call :meth:`decode` with it as
+    /// the argument.
+    ///
+    /// This method was intended to be used from the ``tag_hook`` hook when an
+    /// object needs to be decoded separately from the rest but while still
+    /// taking advantage of the shared value registry.
+    ///
+    /// :param bytes buf: the buffer from which to decode a CBOR object
+    #[pyo3(signature = (buf: "bytes", /))]
+    pub fn decode_from_bytes<'py>(
+        slf: &Bound<'py, Self>,
+        buf: Bound<'py, PyBytes>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut this = slf.borrow_mut();
+        let fp = this.fp.take();
+        let read_position = replace(&mut this.read_position, 0);
+        let available_bytes = replace(&mut this.available_bytes, buf.len()?);
+        let buffer = replace(&mut this.buffer, Some(buf.unbind()));
+        drop(this);
+
+        let result = Self::decode(slf);
+
+        this = slf.borrow_mut();
+        this.fp = fp;
+        this.buffer = buffer;
+        this.read_position = read_position;
+        this.available_bytes = available_bytes;
+        result
+    }
+
+    /// Decode a CBOR sequence (:rfc:`8742`) from the stream.
+    ///
+    /// Returns an iterator that decodes and yields one Python object per top-level CBOR
+    /// item, re-using this decoder's configuration (``tag_hook``, ``object_hook``,
+    /// ``str_errors`` and read buffer) for each item, until the underlying ``fp`` is
+    /// exhausted. A clean end of stream (a zero-length read right before the start of an
+    /// item) stops the iteration; a zero-length read in the middle of an item is still
+    /// reported as a :exc:`.CBORDecodeEOF`.
+    pub fn decode_sequence(slf: &Bound<'_, Self>) -> PyResult<Py<CBORSequenceIterator>> {
+        Py::new(
+            slf.py(),
+            CBORSequenceIterator {
+                decoder: slf.clone().unbind(),
+            },
+        )
+    }
+
+    /// Decode the next item from the stream as a flat stream of events instead of a fully
+    /// materialized Python object.
+    ///
+    /// Returns an iterator yielding, in order: :class:`.ArrayStartToken`/:class:`.MapStartToken`
+    /// when entering an array or map, :class:`.TagStartToken` when entering a semantically
+    /// tagged value, a plain Python value for each scalar (int, float, bytes, str, bool,
+    /// :data:`None`, :data:`.undefined` or a decoded simple value), and a matching
+    /// :data:`.container_end` or :data:`.break_marker` once every array, map or tagged value
+    /// started this way has consumed its declared number of children (or, for indefinite-length
+    /// arrays and maps, once the CBOR break marker is read). The iterator is exhausted once the
+    /// single top-level item has been fully emitted. This lets callers walk huge payloads
+    /// without ever allocating the corresponding :class:`list`/:class:`dict` objects.
+    /// Enforces :attr:`max_depth`, :attr:`max_array_length`, :attr:`max_map_pairs`,
+    /// :attr:`max_total_items` and :attr:`max_bytes`, the same as :meth:`decode`.
+    pub fn decode_tokens(slf: &Bound<'_, Self>) -> PyResult<Py<CBORTokenIterator>> {
+        Py::new(
+            slf.py(),
+            CBORTokenIterator {
+                decoder: slf.clone().unbind(),
+                stack: RefCell::new(Vec::new()),
+                done: Cell::new(false),
+            },
+        )
+    }
+
+    //
+    // Decoders for major tags (0-7)
+    //
+
+    /// Decode the length of the next item.
+    ///
+    /// This is a low-level operation that may be needed by custom decoder callbacks.
+    ///
+    /// :param int subtype:
+    /// :return: the length of the item, or :data:`None` to indicate an indefinite-length item
+    /// :rtype: int | None
+    fn decode_length(&mut self, py: Python<'_>, subtype: u8) -> PyResult<Option<usize>> {
+        let length = match subtype {
+            ..24 => Some(subtype as usize),
+            24 => {
+                let value = self.read_exact::<1>(py)?[0] as usize;
+                if self.canonical && value < 24 {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    );
+                } else if self.warn_on_noncanonical && value < 24 {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    )?;
+                }
+                Some(value)
+            }
+            25 => {
+                let value = u16::from_be_bytes(self.read_exact(py)?) as usize;
+                if self.canonical && value <= u8::MAX as usize {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    );
+                } else if self.warn_on_noncanonical && value <= u8::MAX as usize {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    )?;
+                }
+                Some(value)
+            }
+            26 => {
+                let value = u32::from_be_bytes(self.read_exact(py)?) as usize;
+                if self.canonical && value <= u16::MAX as usize {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    );
+                } else if self.warn_on_noncanonical && value <= u16::MAX as usize {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    )?;
+                }
+                Some(value)
+            }
+            27 => {
+                let value = u64::from_be_bytes(self.read_exact(py)?) as usize;
+                if self.canonical && value <= u32::MAX as usize {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    );
+                } else if self.warn_on_noncanonical && value <= u32::MAX as usize {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: length not encoded in the shortest possible form",
+                    )?;
+                }
+                Some(value)
+            }
+            31 => None,
+            _ => {
+                let msg = format!("unknown unsigned integer subtype 0x{subtype:x}");
+                raise_cbor_error(py, "CBORDecodeValueError", msg.as_str())?
+            }
+        };
+        Ok(length)
+    }
+
+    #[pyo3(signature = (subtype: "int"))]
+    fn decode_uint<'py>(&mut self, py: Python<'py>, subtype: u8) -> PyResult<Bound<'py, PyAny>> {
+        // Major tag 0
+        let uint = self.decode_length_finite(py, subtype)?;
+        let py_int = uint.into_bound_py_any(py)?;
+        Ok(py_int)
+    }
+
+    #[pyo3(signature = (subtype: "int"))]
+    fn decode_negint<'py>(&mut self, py: Python<'py>, subtype: u8) -> PyResult<Bound<'py, PyAny>> {
+        // Major tag 1
+        let uint = self.decode_length_finite(py, subtype)?;
+        let signed_int = -(uint as i128) - 1;
+        let py_int = signed_int.into_bound_py_any(py)?;
+        Ok(py_int)
+    }
+
+    #[pyo3(signature = (subtype: "int"))]
+    fn decode_bytestring<'py>(
+        &mut self,
+        py: Python<'py>,
+        subtype: u8,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        // Major tag 2
+        match self.decode_length(py, subtype)? {
+            None => {
+                if self.canonical {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: indefinite-length byte strings are not allowed",
+                    );
+                } else if self.warn_on_noncanonical {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: indefinite-length byte strings are not allowed",
+                    )?;
+                }
+                // Indefinite length -- always copied, one chunk at a time
+                let mut bytes = PyBytes::new(py, b"");
+                let mut total_length: usize = 0;
+                let sys_maxsize = *SYS_MAXSIZE.get(py).unwrap();
+                loop {
+                    let (major_type, subtype) = self.read_major_and_subtype(py)?;
+                    match (major_type, subtype) {
+                        (2, _) => {
+                            let length = self.decode_length_finite(py, subtype)?;
+                            if length > sys_maxsize {
+                                return raise_cbor_error(
+                                    py,
+                                    "CBORDecodeValueError",
+                                    format!(
+                                        "chunk too long in an indefinite bytestring chunk: {length}"
+                                    )
+                                    .as_str(),
+                                );
+                            }
+                            total_length += length;
+                            let chunk = self.read(py, length)?;
+                            bytes = bytes.add(chunk)?.cast_into()?;
+                        }
+                        (7, 31) => break, // break marker
+                        _ => {
+                            return raise_cbor_error(
+                                py,
+                                "CBORDecodeValueError",
+                                format!(
+                                    "non-byte string (major type {major_type}) found in indefinite \
+                                    length byte string"
+                                )
+                                .as_str(),
+                            );
+                        }
+                    }
+                }
+                self.add_string_to_namespace(&bytes, total_length);
+                Ok(bytes.into_any())
+            }
+            Some(length) if self.zero_copy && self.available_bytes >= length => {
+                let view = self.read_view(py, length)?;
+                self.add_string_to_namespace(&view, length);
+                Ok(view)
+            }
+            Some(length) if length <= 65536 => {
                 let bytes = self.read(py, length)?;
-                (PyBytes::new(py, &bytes), length)
+                let decoded = PyBytes::new(py, &bytes);
+                self.add_string_to_namespace(&decoded, length);
+                Ok(decoded.into_any())
             }
             Some(length) => {
                 // Incrementally read the bytestring, in chunks of 65536 bytes
@@ -668,11 +1618,10 @@ impl CBORDecoder {
                     remaining_length -= chunk_size;
                     bytes = bytes.add(chunk)?.cast_into()?;
                 }
-                (bytes, length)
+                self.add_string_to_namespace(&bytes, length);
+                Ok(bytes.into_any())
             }
-        };
-        self.add_string_to_namespace(&decoded, length);
-        Ok(decoded)
+        }
     }
 
     #[pyo3(signature = (subtype: "int"))]
@@ -684,6 +1633,18 @@ impl CBORDecoder {
         // Major tag 3
         let (decoded, length) = match self.decode_length(py, subtype)? {
             None => {
+                if self.canonical {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: indefinite-length text strings are not allowed",
+                    );
+                } else if self.warn_on_noncanonical {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: indefinite-length text strings are not allowed",
+                    )?;
+                }
                 // Indefinite length
                 let mut string = PyString::new(py, "");
                 let mut total_length: usize = 0;
@@ -797,13 +1758,39 @@ impl CBORDecoder {
         let py = slf.py();
         let mut this = slf.borrow_mut();
         let length = this.decode_length(py, subtype)?;
+        if length.is_none() && this.canonical {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                "non-canonical CBOR: indefinite-length arrays are not allowed",
+            );
+        } else if length.is_none() && this.warn_on_noncanonical {
+            emit_cbor_warning(
+                py,
+                "non-canonical CBOR: indefinite-length arrays are not allowed",
+            )?;
+        }
+        if let Some(length) = length
+            && let Some(max_array_length) = this.max_array_length
+            && length > max_array_length
+        {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeLimitError",
+                format!(
+                    "declared array length ({length}) exceeds the configured maximum \
+                     ({max_array_length})"
+                )
+                .as_str(),
+            );
+        }
         match (length, this.immutable) {
             (None, true) => {
                 // Tuple of indefinite length
                 let mut items = Vec::<Bound<'_, PyAny>>::new();
                 drop(this);
                 loop {
-                    let obj = Self::decode(slf)?;
+                    let obj = Self::with_context(slf, "array item", || Self::decode(slf))?;
                     if obj.is_exact_instance_of::<BreakMarkerType>() {
                         let tuple = PyTuple::new(py, items)?;
                         slf.borrow_mut().set_shareable(&tuple);
@@ -818,7 +1805,7 @@ impl CBORDecoder {
                 this.set_shareable(&list);
                 drop(this);
                 loop {
-                    let obj = Self::decode(slf)?;
+                    let obj = Self::with_context(slf, "array item", || Self::decode(slf))?;
                     if obj.is_exact_instance_of::<BreakMarkerType>() {
                         break Ok(list.into_any());
                     } else {
@@ -831,7 +1818,7 @@ impl CBORDecoder {
                 drop(this);
                 let mut items = Vec::<Bound<'_, PyAny>>::with_capacity(length);
                 for _ in 0..length {
-                    items.push(Self::decode(slf)?);
+                    items.push(Self::with_context(slf, "array item", || Self::decode(slf))?);
                 }
                 let tuple = PyTuple::new(py, items)?;
                 slf.borrow_mut().set_shareable(&tuple);
@@ -843,7 +1830,7 @@ impl CBORDecoder {
                 this.set_shareable(&list);
                 drop(this);
                 for _ in 0..length {
-                    list.append(Self::decode(slf)?)?;
+                    list.append(Self::with_context(slf, "array item", || Self::decode(slf))?)?;
                 }
                 Ok(list.into_any())
             }
@@ -857,24 +1844,91 @@ impl CBORDecoder {
         let mut this = slf.borrow_mut();
         let dict = PyDict::new(py);
         this.set_shareable(&dict);
+        let canonical = this.canonical;
+        let warn_on_noncanonical = this.warn_on_noncanonical;
+        let max_map_pairs = this.max_map_pairs;
+        let mut prev_key_bytes: Option<Vec<u8>> = None;
+        let mut seen_key_bytes: std::collections::HashSet<Vec<u8>> =
+            std::collections::HashSet::new();
         match this.decode_length(py, subtype)? {
             None => {
+                if canonical {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: indefinite-length maps are not allowed",
+                    );
+                } else if warn_on_noncanonical {
+                    emit_cbor_warning(
+                        py,
+                        "non-canonical CBOR: indefinite-length maps are not allowed",
+                    )?;
+                }
                 // Indefinite length
                 drop(this);
                 loop {
-                    let key = Self::with_immutable(slf, || Self::decode(slf))?;
+                    let key = Self::with_context(slf, "map key", || {
+                        Self::with_immutable(slf, || Self::decode(slf))
+                    })?;
                     if key.is_exact_instance_of::<BreakMarkerType>() {
                         break;
                     }
-                    let value = Self::decode(slf)?;
+                    if warn_on_noncanonical
+                        && let Some(key_bytes) = canonical_key_bytes(&key)?
+                        && !seen_key_bytes.insert(key_bytes)
+                    {
+                        emit_cbor_warning(py, "non-canonical CBOR: duplicate map key")?;
+                    }
+                    let value = Self::with_context(slf, "map value", || Self::decode(slf))?;
                     dict.set_item(key, value)?;
                 }
             }
             Some(length) => {
+                if let Some(max_map_pairs) = max_map_pairs
+                    && length > max_map_pairs
+                {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeLimitError",
+                        format!(
+                            "declared map pair count ({length}) exceeds the configured \
+                             maximum ({max_map_pairs})"
+                        )
+                        .as_str(),
+                    );
+                }
                 drop(this);
                 for _ in 0..length {
-                    let key = Self::with_immutable(slf, || Self::decode(slf))?;
-                    let value = Self::decode(slf)?;
+                    let key = Self::with_context(slf, "map key", || {
+                        Self::with_immutable(slf, || Self::decode(slf))
+                    })?;
+                    if canonical && let Some(key_bytes) = canonical_key_bytes(&key)? {
+                        match &prev_key_bytes {
+                            Some(prev) if *prev == key_bytes => {
+                                return raise_cbor_error(
+                                    py,
+                                    "CBORDecodeValueError",
+                                    "non-canonical CBOR: duplicate map key",
+                                );
+                            }
+                            Some(prev) if *prev > key_bytes => {
+                                return raise_cbor_error(
+                                    py,
+                                    "CBORDecodeValueError",
+                                    "non-canonical CBOR: map keys are not in canonical \
+                                     (bytewise sorted) order",
+                                );
+                            }
+                            _ => {}
+                        }
+                        prev_key_bytes = Some(key_bytes);
+                    } else if warn_on_noncanonical
+                        && let Some(key_bytes) = canonical_key_bytes(&key)?
+                        && !seen_key_bytes.insert(key_bytes)
+                    {
+                        emit_cbor_warning(py, "non-canonical CBOR: duplicate map key")?;
+                    }
+                    let value = Self::with_context(slf, "map value", || Self::decode(slf))?;
                     dict.set_item(key, value)?;
                 }
             }
@@ -896,7 +1950,7 @@ impl CBORDecoder {
         // If we're constructing an immutable map, wrap the dict in a FrozenDict
         if slf.borrow().immutable {
             let args = PyTuple::new(py, [dict])?;
-            FrozenDict::new(&args)?.into_bound_py_any(py)
+            FrozenDict::new(&args, None)?.into_bound_py_any(py)
         } else {
             Ok(dict.into_any())
         }
@@ -941,6 +1995,8 @@ impl CBORDecoder {
             258 => Self::decode_set(slf),
             260 => Self::decode_ipaddress(slf),
             261 => Self::decode_ipnetwork(slf),
+            1001 => Self::decode_extended_time(slf),
+            1002 => Self::decode_duration(slf),
             1004 => Self::decode_date_string(slf),
             43000 => Self::decode_complex(slf),
             55799 => Self::decode_self_describe_cbor(slf),
@@ -975,17 +2031,14 @@ impl CBORDecoder {
         // Major tag 7
         // let py = slf.py();
         match subtype {
-            0..20 => {
-                let value = subtype.into_pyobject(py)?;
-                CBORSimpleValue::new(value)?.into_bound_py_any(py)
-            }
+            0..20 => CBORSimpleValue::intern(py, subtype)?.into_bound_py_any(py),
             20 => Ok(false.into_bound_py_any(py)?),
             21 => Ok(true.into_bound_py_any(py)?),
             22 => Ok(py.None().into_bound_py_any(py)?),
             23 => Ok(UNDEFINED.get(py).unwrap().into_bound_py_any(py)?),
             24 => {
                 let value = self.read_exact::<1>(py)?[0];
-                CBORSimpleValue::new(value.into_pyobject(py)?)?.into_bound_py_any(py)
+                CBORSimpleValue::intern(py, value)?.into_bound_py_any(py)
             }
             25 => {
                 let bytes = self.read_exact::<2>(py)?;
@@ -993,11 +2046,27 @@ impl CBORDecoder {
             }
             26 => {
                 let bytes = self.read_exact::<4>(py)?;
-                f32::from_be_bytes(bytes).into_bound_py_any(py)
+                let value = f32::from_be_bytes(bytes);
+                if self.canonical && f16::from_f32(value).to_f32() == value {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: float not encoded in the shortest possible width",
+                    );
+                }
+                value.into_bound_py_any(py)
             }
             27 => {
                 let bytes = self.read_exact::<8>(py)?;
-                f64::from_be_bytes(bytes).into_bound_py_any(py)
+                let value = f64::from_be_bytes(bytes);
+                if self.canonical && (value as f32) as f64 == value {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "non-canonical CBOR: float not encoded in the shortest possible width",
+                    );
+                }
+                value.into_bound_py_any(py)
             }
             31 => Ok(BREAK_MARKER.get(py).unwrap().into_bound_py_any(py)?),
             _ => {
@@ -1294,9 +2363,23 @@ impl CBORDecoder {
     fn decode_mime<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
         // Semantic tag 36
         let py = slf.py();
+        let mime_policy = slf.borrow().mime_policy.clone_ref(py);
         let value = Self::decode(slf)?;
-        let parser = EMAIL_PARSER.get(py)?.call0()?;
-        match parser.call_method1(intern!(py, "parsestr"), (value,)) {
+        let kwargs = PyDict::new(py);
+        if mime_policy.bind(py).to_str()? == "default" {
+            kwargs.set_item(intern!(py, "policy"), EMAIL_POLICY_DEFAULT.get(py)?)?;
+        }
+
+        // Bytestring content may carry non-ASCII or binary transfer encodings that only
+        // BytesParser handles correctly; str content goes through the text parser as before.
+        let message = if let Ok(bytes) = value.cast::<PyBytes>() {
+            let parser = EMAIL_BYTES_PARSER.get(py)?.call((), Some(&kwargs))?;
+            parser.call_method1(intern!(py, "parsebytes"), (bytes,))
+        } else {
+            let parser = EMAIL_PARSER.get(py)?.call((), Some(&kwargs))?;
+            parser.call_method1(intern!(py, "parsestr"), (value,))
+        };
+        match message {
             Ok(message) => Ok(message),
             Err(e) => {
                 raise_cbor_error_from(py, "CBORDecodeValueError", "error decoding MIME message", e)
@@ -1323,7 +2406,19 @@ impl CBORDecoder {
         let py = slf.py();
         let value = Self::with_immutable(slf, || Self::decode(slf))?;
         let addr = if let Ok(bytes) = value.cast::<PyBytes>() {
-            // The decoded value was a bytestring, so this is an IPv4 address
+            // The decoded value was a bytestring, so this is a plain IPv4 address. Validate
+            // its length natively, but still construct the result via the ipaddress module so
+            // we return a real ipaddress.IPv4Address instead of something pyo3 has no built-in
+            // conversion for.
+            let data = bytes.as_bytes();
+            let _: [u8; 4] = data.try_into().map_err(|_| {
+                create_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("invalid IPv4 address length ({})", data.len()).as_str(),
+                    None,
+                )
+            })?;
             IPV4ADDRESS_TYPE.get(py)?.call1((bytes,))?
         } else if let Ok(tuple) = value.cast_into::<PyTuple>()
             && tuple.len() == 2
@@ -1333,23 +2428,41 @@ impl CBORDecoder {
             // (bytes, int) -> interface
             let first_item = tuple.get_item(0)?;
             let second_item = tuple.get_item(1)?;
-            if let Ok(prefix) = first_item.cast::<PyInt>()
+            let (class, address, prefix) = if let Ok(prefix) = first_item.cast::<PyInt>()
                 && let Ok(address) = second_item.cast::<PyBytes>()
             {
-                let mut address_vec: Vec<u8> = address.extract()?;
-                address_vec.resize(4, 0);
-                IPV4NETWORK_TYPE.get(py)?.call1(((address_vec, prefix),))?
+                (IPV4NETWORK_TYPE.get(py)?, address, prefix)
             } else if let Ok(address) = first_item.cast::<PyBytes>()
                 && let Ok(prefix) = second_item.cast::<PyInt>()
             {
-                IPV4INTERFACE_TYPE.get(py)?.call1(((address, prefix),))?
+                (IPV4INTERFACE_TYPE.get(py)?, address, prefix)
             } else {
                 return raise_cbor_error(
                     py,
                     "CBORDecodeValueError",
                     "error decoding IPv4: invalid types in input array",
                 );
+            };
+            let prefix_len: u8 = prefix.extract()?;
+            if prefix_len > 32 {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("invalid IPv4 prefix length ({prefix_len})").as_str(),
+                );
+            }
+            let data = address.as_bytes();
+            if data.len() > 4 {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("invalid IPv4 address length ({})", data.len()).as_str(),
+                );
             }
+            let mut octets = [0u8; 4];
+            octets[..data.len()].copy_from_slice(data);
+            let packed = PyBytes::new(py, &octets);
+            class.call1(((packed, prefix_len),))?
         } else {
             return raise_cbor_error(
                 py,
@@ -1363,11 +2476,22 @@ impl CBORDecoder {
     fn decode_ipv6<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
         // Semantic tag 54
         let py = slf.py();
-        let ipv6addr_class = IPV6ADDRESS_TYPE.get(py)?;
         let value = Self::with_immutable(slf, || Self::decode(slf))?;
         let addr = if let Ok(bytes) = value.cast::<PyBytes>() {
-            // The decoded value was a bytestring, so this is an IPv6 address
-            ipv6addr_class.call1((bytes,))?
+            // The decoded value was a bytestring, so this is a plain IPv6 address. Validate
+            // its length natively, but still construct the result via the ipaddress module so
+            // we return a real ipaddress.IPv6Address instead of something pyo3 has no built-in
+            // conversion for.
+            let data = bytes.as_bytes();
+            let _: [u8; 16] = data.try_into().map_err(|_| {
+                create_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("invalid IPv6 address length ({})", data.len()).as_str(),
+                    None,
+                )
+            })?;
+            IPV6ADDRESS_TYPE.get(py)?.call1((bytes,))?
         } else if let Ok(tuple) = value.cast_into::<PyTuple>()
             && (2..=3).contains(&tuple.len())
         {
@@ -1378,33 +2502,46 @@ impl CBORDecoder {
             let first_item = tuple.get_item(0)?;
             let second_item = tuple.get_item(1)?;
             let zone_id = tuple.get_item(2).ok();
-            let (class, addr_bytes, prefix) = if let Ok(prefix) = first_item.cast::<PyInt>()
+            let (class, address, prefix) = if let Ok(prefix) = first_item.cast::<PyInt>()
                 && let Ok(address) = second_item.cast::<PyBytes>()
             {
-                let mut address_vec: Vec<u8> = address.extract()?;
-                address_vec.resize(16, 0);
-                Ok((
-                    IPV6NETWORK_TYPE.get(py)?,
-                    PyBytes::new(py, address_vec.as_slice()),
-                    prefix,
-                ))
+                (IPV6NETWORK_TYPE.get(py)?, address, prefix)
             } else if let Ok(address) = first_item.cast_into::<PyBytes>()
                 && let Ok(prefix) = second_item.cast::<PyInt>()
             {
-                Ok((IPV6INTERFACE_TYPE.get(py)?, address, prefix))
+                (IPV6INTERFACE_TYPE.get(py)?, address, prefix)
             } else {
-                raise_cbor_error(
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    "error decoding IPv6: invalid types in input array",
+                );
+            };
+            let prefix_len: u8 = prefix.extract()?;
+            if prefix_len > 128 {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("invalid IPv6 prefix length ({prefix_len})").as_str(),
+                );
+            }
+            let data = address.as_bytes();
+            if data.len() > 16 {
+                return raise_cbor_error(
                     py,
                     "CBORDecodeValueError",
-                    "error decoding IPv6: invalid types in input array",
-                )
-            }?;
-            let addr_obj = ipv6addr_class.call1((addr_bytes,))?;
-
-            // Format the zone ID suffix if a zone ID was included
-            // (bytes or integer as the last item of a 3-tuple)
-            let zone_id_suffix = if let Some(zone_id) = zone_id {
-                if let Ok(zone_id_bytes) = zone_id.cast::<PyBytes>() {
+                    format!("invalid IPv6 address length ({})", data.len()).as_str(),
+                );
+            }
+            let mut octets = [0u8; 16];
+            octets[..data.len()].copy_from_slice(data);
+
+            // Compute the scoped address natively and format it only if a zone ID was
+            // included (bytes or integer as the last item of a 3-tuple); otherwise skip
+            // string formatting entirely and hand the packed bytes straight to the
+            // network/interface constructor.
+            if let Some(zone_id) = zone_id {
+                let zone_id_suffix = if let Ok(zone_id_bytes) = zone_id.cast::<PyBytes>() {
                     let zone_id_str = String::from_utf8(zone_id_bytes.as_bytes().to_vec())?;
                     format!("%{zone_id_str}")
                 } else if let Ok(zone_id_int) = zone_id.cast::<PyInt>() {
@@ -1415,13 +2552,13 @@ impl CBORDecoder {
                         "CBORDecodeValueError",
                         "error decoding IPv6: zone ID must be an integer or a bytestring",
                     );
-                }
+                };
+                let addr = Ipv6Addr::from(octets);
+                class.call1((format!("{addr}{zone_id_suffix}/{prefix_len}"),))?
             } else {
-                String::default()
-            };
-
-            let formatted_addr = format!("{addr_obj}{zone_id_suffix}/{prefix}");
-            class.call1((formatted_addr,))?
+                let packed = PyBytes::new(py, &octets);
+                class.call1(((packed, prefix_len),))?
+            }
         } else {
             return raise_cbor_error(
                 py,
@@ -1480,7 +2617,18 @@ impl CBORDecoder {
             4 | 16 => {
                 IPADDRESS_FUNC.get(py)?.call1((value,))
             }
-            6 => Ok(Bound::new(py, CBORTag::new_internal(260, value.into_any()))?.into_any()), // MAC address
+            6 | 8 => {
+                // EUI-48 (MAC) or EUI-64 link-layer address: format it natively as the
+                // conventional colon-separated hex string instead of leaving it as an
+                // opaque CBORTag the caller must special-case.
+                let formatted = value
+                    .as_bytes()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(":");
+                formatted.into_bound_py_any(py)
+            }
             length => raise_cbor_error(
                 py,
                 "CBORDecodeValueError",
@@ -1533,6 +2681,86 @@ impl CBORDecoder {
         Ok(date)
     }
 
+    fn decode_extended_time<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        // Semantic tag 1001 (:rfc:`9581` extended time)
+        let py = slf.py();
+        let value = Self::decode(slf)?;
+        let base_time = value.get_item(1).map_err(|e| {
+            create_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                "error decoding extended time: map has no key 1 (base time)",
+                Some(e),
+            )
+        })?;
+
+        // The fractional-second, leap-second and clock-quality keys defined by the RFC don't
+        // have a representation in `datetime.datetime`, so they're ignored here; only the base
+        // POSIX time (key 1) is used, exactly like the plain epoch timestamp tag.
+        let utc = UTC.get(py)?;
+        DATETIME_FROMTIMESTAMP.get(py)?
+            .call1((base_time, utc))
+            .map_err(|e| {
+                create_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    "error decoding datetime from extended time",
+                    Some(e),
+                )
+            })
+    }
+
+    fn decode_duration<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        // Semantic tag 1002 (:rfc:`9581` duration)
+        let py = slf.py();
+        let value = Self::decode(slf)?;
+
+        // The content is either a map of components (1=seconds, 2=minutes, 3=hours, 4=days;
+        // unrecognized components -- e.g. clock-quality keys -- are ignored) or, in the
+        // simpler registered form, a plain number of seconds.
+        let total_seconds: f64 = match value.call_method0(intern!(py, "items")) {
+            Ok(items) => {
+                let mut total = 0.0;
+                for item in items.try_iter()? {
+                    let (key, component): (i64, f64) = item?.extract().map_err(|e| {
+                        create_cbor_error(
+                            py,
+                            "CBORDecodeValueError",
+                            "error decoding duration: map keys and values must be numbers",
+                            Some(e),
+                        )
+                    })?;
+                    let seconds_per_unit = match key {
+                        1 => 1.0,
+                        2 => 60.0,
+                        3 => 3600.0,
+                        4 => 86400.0,
+                        _ => continue,
+                    };
+                    total += component * seconds_per_unit;
+                }
+                total
+            }
+            Err(_) => value.extract().map_err(|e| {
+                create_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    "error decoding duration: input value must be a map or a number",
+                    Some(e),
+                )
+            })?,
+        };
+
+        let whole_seconds = total_seconds.trunc() as i64;
+        let microseconds = ((total_seconds - total_seconds.trunc()) * 1_000_000.0).round() as i64;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "seconds"), whole_seconds)?;
+        kwargs.set_item(intern!(py, "microseconds"), microseconds)?;
+        TIMEDELTA_TYPE.get(py)?.call((), Some(&kwargs)).map_err(|e| {
+            create_cbor_error(py, "CBORDecodeValueError", "error decoding duration", Some(e))
+        })
+    }
+
     fn decode_complex<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
         // Semantic tag 43000
         let py = slf.py();
@@ -1566,3 +2794,639 @@ impl CBORDecoder {
         Self::decode(slf)
     }
 }
+
+/// Iterator over the CBOR items of a sequence (:rfc:`8742`), as returned by
+/// :meth:`CBORDecoder.decode_sequence` and :func:`load_sequence`.
+#[pyclass(module = "cbor2")]
+pub struct CBORSequenceIterator {
+    decoder: Py<CBORDecoder>,
+}
+
+#[pymethods]
+impl CBORSequenceIterator {
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let decoder = self.decoder.bind(py);
+        let has_next = decoder.borrow_mut().try_peek_first_byte(py)?;
+        if !has_next {
+            return Ok(None);
+        }
+
+        Ok(Some(CBORDecoder::decode(decoder)?))
+    }
+}
+
+/// One open array, map or tag frame in the stack driving [`CBORTokenIterator`]. `Counted`
+/// tracks the number of child slots (items for an array, `2 * length` for a map, 1 for a tag)
+/// still owed before the container closes; `Indefinite` containers instead close when a break marker is read.
+enum TokenFrame {
+    Counted(usize),
+    Indefinite,
+}
+
+/// Iterator over the flat event stream of a single CBOR item, as returned by
+/// :meth:`CBORDecoder.decode_tokens`.
+#[pyclass(module = "cbor2")]
+pub struct CBORTokenIterator {
+    decoder: Py<CBORDecoder>,
+    stack: RefCell<Vec<TokenFrame>>,
+    done: Cell<bool>,
+}
+
+impl CBORTokenIterator {
+    /// Account for the data item about to be emitted filling one child slot of whatever
+    /// container is currently on top of the stack (a no-op at the top level, where the
+    /// stack is empty).
+    fn decrement_parent(&self) {
+        if let Some(TokenFrame::Counted(remaining)) = self.stack.borrow_mut().last_mut() {
+            *remaining -= 1;
+        }
+    }
+
+    /// The stream-level item has been fully emitted once the frame stack empties back out,
+    /// whether that's a bare top-level scalar or the close of the outermost container.
+    fn finish_if_exhausted(&self) {
+        if self.stack.borrow().is_empty() {
+            self.done.set(true);
+        }
+    }
+}
+
+#[pymethods]
+impl CBORTokenIterator {
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if self.done.get() {
+            return Ok(None);
+        }
+
+        // A definite-length container whose slots are all filled closes on the next pull,
+        // before any more bytes are read from the stream. Its own slot in its parent (if any)
+        // was already accounted for when its *start* token was emitted.
+        let closed_definite = matches!(self.stack.borrow().last(), Some(TokenFrame::Counted(0)));
+        if closed_definite {
+            self.stack.borrow_mut().pop();
+            self.finish_if_exhausted();
+            if self.done.get() {
+                self.decoder.bind(py).borrow_mut().total_items = 0;
+            }
+            return Ok(Some(CONTAINER_END.get(py).unwrap().into_bound_py_any(py)?));
+        }
+
+        let decoder = self.decoder.bind(py);
+        {
+            let mut this = decoder.borrow_mut();
+            if let Some(max_bytes) = this.max_bytes
+                && this.consumed > max_bytes
+            {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeLimitError",
+                    format!("maximum input size ({max_bytes} bytes) exceeded").as_str(),
+                );
+            }
+
+            this.total_items += 1;
+            if let Some(max_total_items) = this.max_total_items
+                && this.total_items > max_total_items
+            {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeLimitError",
+                    format!("maximum total item count ({max_total_items}) exceeded").as_str(),
+                );
+            }
+        }
+        let (major_type, subtype) = decoder.borrow_mut().read_major_and_subtype(py)?;
+        let event: Bound<'py, PyAny> = match major_type {
+            0 => {
+                let value = decoder.borrow_mut().decode_uint(py, subtype)?;
+                self.decrement_parent();
+                self.finish_if_exhausted();
+                value
+            }
+            1 => {
+                let value = decoder.borrow_mut().decode_negint(py, subtype)?;
+                self.decrement_parent();
+                self.finish_if_exhausted();
+                value
+            }
+            2 => {
+                let value = decoder.borrow_mut().decode_bytestring(py, subtype)?;
+                self.decrement_parent();
+                self.finish_if_exhausted();
+                value
+            }
+            3 => {
+                let value = decoder.borrow_mut().decode_string(py, subtype)?.into_any();
+                self.decrement_parent();
+                self.finish_if_exhausted();
+                value
+            }
+            4 => {
+                let length = decoder.borrow_mut().decode_length(py, subtype)?;
+                if let Some(n) = length
+                    && let Some(max_array_length) = decoder.borrow().max_array_length
+                    && n > max_array_length
+                {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeLimitError",
+                        format!(
+                            "declared array length ({n}) exceeds the configured maximum \
+                             ({max_array_length})"
+                        )
+                        .as_str(),
+                    );
+                }
+                if self.stack.borrow().len() >= decoder.borrow().max_depth {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeError",
+                        format!(
+                            "maximum container nesting depth ({}) exceeded",
+                            decoder.borrow().max_depth
+                        )
+                        .as_str(),
+                    );
+                }
+                self.decrement_parent();
+                self.stack.borrow_mut().push(match length {
+                    Some(n) => TokenFrame::Counted(n),
+                    None => TokenFrame::Indefinite,
+                });
+                Bound::new(py, ArrayStartToken::new(length))?.into_any()
+            }
+            5 => {
+                let length = decoder.borrow_mut().decode_length(py, subtype)?;
+                if let Some(n) = length
+                    && let Some(max_map_pairs) = decoder.borrow().max_map_pairs
+                    && n > max_map_pairs
+                {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeLimitError",
+                        format!(
+                            "declared map pair count ({n}) exceeds the configured maximum \
+                             ({max_map_pairs})"
+                        )
+                        .as_str(),
+                    );
+                }
+                if self.stack.borrow().len() >= decoder.borrow().max_depth {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeError",
+                        format!(
+                            "maximum container nesting depth ({}) exceeded",
+                            decoder.borrow().max_depth
+                        )
+                        .as_str(),
+                    );
+                }
+                self.decrement_parent();
+                self.stack.borrow_mut().push(match length {
+                    Some(n) => TokenFrame::Counted(n * 2),
+                    None => TokenFrame::Indefinite,
+                });
+                Bound::new(py, MapStartToken::new(length))?.into_any()
+            }
+            6 => {
+                let tag = decoder.borrow_mut().decode_length_finite(py, subtype)? as u64;
+                if self.stack.borrow().len() >= decoder.borrow().max_depth {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeError",
+                        format!(
+                            "maximum container nesting depth ({}) exceeded",
+                            decoder.borrow().max_depth
+                        )
+                        .as_str(),
+                    );
+                }
+                self.decrement_parent();
+                self.stack.borrow_mut().push(TokenFrame::Counted(1));
+                Bound::new(py, TagStartToken::new(tag))?.into_any()
+            }
+            7 => {
+                let value = decoder.borrow_mut().decode_special(py, subtype)?;
+                if value.is_exact_instance_of::<BreakMarkerType>() {
+                    // The indefinite-length container this closes already had its own slot
+                    // (if any) accounted for when its start token was emitted.
+                    self.stack.borrow_mut().pop();
+                    self.finish_if_exhausted();
+                } else {
+                    self.decrement_parent();
+                    self.finish_if_exhausted();
+                }
+                value
+            }
+            _ => {
+                return raise_cbor_error(
+                    py,
+                    "CBORDecodeError",
+                    format!("invalid major type: {major_type}").as_str(),
+                );
+            }
+        };
+
+        if self.done.get() {
+            decoder.borrow_mut().total_items = 0;
+        }
+        Ok(Some(event))
+    }
+}
+
+enum FeedFrame {
+    Array { items: Vec<Py<PyAny>>, remaining: usize },
+    IndefArray { items: Vec<Py<PyAny>> },
+    Map { dict: Py<PyDict>, remaining: usize, pending_key: Option<Py<PyAny>> },
+    IndefMap { dict: Py<PyDict>, pending_key: Option<Py<PyAny>> },
+    Tag { tagnum: u64 },
+    IndefBytes { chunks: Vec<u8> },
+    IndefText { chunks: String },
+}
+
+struct PendingPayload {
+    is_text: bool,
+    remaining: usize,
+    data: Vec<u8>,
+}
+
+/// Parse a single CBOR item head (major type, subtype and any following length/value bytes)
+/// out of `bytes`, without consuming anything if it isn't fully present yet.
+///
+/// Returns `Ok(None)` if `bytes` doesn't yet contain a complete head, `Err` for a malformed
+/// subtype, or `Ok(Some((major_type, subtype, value, header_len)))` where `value` is the
+/// subtype's associated unsigned integer (length, tag number, simple value or float bit
+/// pattern, depending on context) and `header_len` is the number of bytes the head occupies.
+fn try_parse_head(bytes: &[u8]) -> Result<Option<(u8, u8, u64, usize)>, String> {
+    let Some(&first) = bytes.first() else {
+        return Ok(None);
+    };
+    let major_type = first >> 5;
+    let subtype = first & 0x1f;
+    let extra_len = match subtype {
+        ..24 => 0,
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        31 => 0,
+        _ => return Err(format!("unknown subtype 0x{subtype:x}")),
+    };
+    if bytes.len() < 1 + extra_len {
+        return Ok(None);
+    }
+    let value = match extra_len {
+        0 if subtype < 24 => subtype as u64,
+        0 => 0, // indefinite-length marker (subtype 31); value is unused
+        1 => bytes[1] as u64,
+        2 => u16::from_be_bytes([bytes[1], bytes[2]]) as u64,
+        4 => u32::from_be_bytes(bytes[1..5].try_into().unwrap()) as u64,
+        8 => u64::from_be_bytes(bytes[1..9].try_into().unwrap()),
+        _ => unreachable!(),
+    };
+    Ok(Some((major_type, subtype, value, 1 + extra_len)))
+}
+
+/// A push-style, non-recursive CBOR decoder for streaming sources (sockets, async
+/// transports) where the whole input isn't available up front.
+///
+/// Unlike :class:`.CBORDecoder`, which reads from a file-like object and recurses through
+/// :meth:`~.CBORDecoder.decode` for each nested value, :class:`FeedDecoder` holds an explicit
+/// stack of in-progress containers and is driven entirely by :meth:`feed`: bytes are appended
+/// as they arrive, and every top-level CBOR item that becomes complete as a result is returned.
+/// Partially received containers (and their already-decoded children) are kept alive on the
+/// stack across calls, so decoding a huge or slow-arriving item never blocks and never
+/// recurses.
+///
+/// This is a deliberately narrower tool than :class:`.CBORDecoder`: it has no ``tag_hook``,
+/// ``object_hook``, shared value or string reference support, and performs no semantic tag
+/// interpretation -- tags are represented as plain :class:`.CBORTag` objects. Pass the decoded
+/// stream through :class:`.CBORDecoder` (e.g. via :func:`loads`) if those features are needed.
+/// For the same reason, ``max_depth`` is the only configurable resource limit: unlike
+/// :class:`.CBORDecoder`, it has no ``max_array_length``, ``max_map_pairs``, ``max_total_items``
+/// or ``max_bytes`` parameter, so callers who need those limits on untrusted input should decode
+/// through :class:`.CBORDecoder` instead.
+///
+/// :param int max_depth: maximum allowed depth for nested containers
+/// :param str_errors:
+///     determines how to handle unicode decoding errors (see the `Error Handlers`_
+///     section in the standard library documentation for details)
+///
+/// .. _Error Handlers: https://docs.python.org/3/library/codecs.html#error-handlers
+#[pyclass(module = "cbor2")]
+pub struct FeedDecoder {
+    buffer: Vec<u8>,
+    pos: usize,
+    stack: Vec<FeedFrame>,
+    pending: Option<PendingPayload>,
+    max_depth: usize,
+    str_errors: Py<PyString>,
+}
+
+impl FeedDecoder {
+    fn push_frame(&mut self, py: Python<'_>, frame: FeedFrame) -> PyResult<()> {
+        if self.stack.len() >= self.max_depth {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                format!("max nesting depth {} exceeded", self.max_depth).as_str(),
+            );
+        }
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    fn decode_utf8<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+        let bytes = PyBytes::new(py, data);
+        match bytes.call_method1(intern!(py, "decode"), ("utf-8", self.str_errors.bind(py))) {
+            Ok(s) => Ok(s),
+            Err(e) => raise_cbor_error_from(py, "CBORDecodeValueError", "error decoding text string", e),
+        }
+    }
+
+    /// Attach a just-completed value to its parent frame, bubbling further completions (a
+    /// container whose last slot was just filled, a tag whose single child just arrived) up
+    /// the stack. Returns the value once it has bubbled all the way past an empty stack, i.e.
+    /// once a full top-level item is ready.
+    fn complete<'py>(
+        &mut self,
+        py: Python<'py>,
+        mut value: Bound<'py, PyAny>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        loop {
+            let Some(frame) = self.stack.pop() else {
+                return Ok(Some(value));
+            };
+            match frame {
+                FeedFrame::Array { mut items, remaining } => {
+                    items.push(value.unbind());
+                    let remaining = remaining - 1;
+                    if remaining == 0 {
+                        value = PyList::new(py, items)?.into_any();
+                        continue;
+                    }
+                    self.stack.push(FeedFrame::Array { items, remaining });
+                    return Ok(None);
+                }
+                FeedFrame::IndefArray { mut items } => {
+                    items.push(value.unbind());
+                    self.stack.push(FeedFrame::IndefArray { items });
+                    return Ok(None);
+                }
+                FeedFrame::Map { dict, remaining, pending_key } => match pending_key {
+                    Some(key) => {
+                        dict.bind(py).set_item(key, value)?;
+                        let remaining = remaining - 1;
+                        if remaining == 0 {
+                            value = dict.bind(py).clone().into_any();
+                            continue;
+                        }
+                        self.stack.push(FeedFrame::Map { dict, remaining, pending_key: None });
+                        return Ok(None);
+                    }
+                    None => {
+                        self.stack.push(FeedFrame::Map { dict, remaining, pending_key: Some(value.unbind()) });
+                        return Ok(None);
+                    }
+                },
+                FeedFrame::IndefMap { dict, pending_key } => match pending_key {
+                    Some(key) => {
+                        dict.bind(py).set_item(key, value)?;
+                        self.stack.push(FeedFrame::IndefMap { dict, pending_key: None });
+                        return Ok(None);
+                    }
+                    None => {
+                        self.stack.push(FeedFrame::IndefMap { dict, pending_key: Some(value.unbind()) });
+                        return Ok(None);
+                    }
+                },
+                FeedFrame::Tag { tagnum } => {
+                    let tag = CBORTag::new_internal(tagnum, value);
+                    value = Bound::new(py, tag)?.into_any();
+                    continue;
+                }
+                FeedFrame::IndefBytes { mut chunks } => {
+                    let chunk: Bound<PyBytes> = value.cast_into().map_err(|e| {
+                        create_cbor_error(
+                            py,
+                            "CBORDecodeValueError",
+                            "non-byte string found in indefinite-length byte string",
+                            Some(PyErr::from(e)),
+                        )
+                    })?;
+                    chunks.extend_from_slice(chunk.as_bytes());
+                    self.stack.push(FeedFrame::IndefBytes { chunks });
+                    return Ok(None);
+                }
+                FeedFrame::IndefText { mut chunks } => {
+                    let chunk: Bound<PyString> = value.cast_into().map_err(|e| {
+                        create_cbor_error(
+                            py,
+                            "CBORDecodeValueError",
+                            "non-text string found in indefinite-length text string",
+                            Some(PyErr::from(e)),
+                        )
+                    })?;
+                    chunks.push_str(chunk.to_str()?);
+                    self.stack.push(FeedFrame::IndefText { chunks });
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Pop and finalize the innermost indefinite-length frame on encountering a break marker.
+    fn close_indefinite<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match self.stack.pop() {
+            Some(FeedFrame::IndefArray { items }) => Ok(PyList::new(py, items)?.into_any()),
+            Some(FeedFrame::IndefMap { dict, pending_key }) => {
+                if pending_key.is_some() {
+                    return raise_cbor_error(
+                        py,
+                        "CBORDecodeValueError",
+                        "missing value for final key in indefinite-length map",
+                    );
+                }
+                Ok(dict.bind(py).clone().into_any())
+            }
+            Some(FeedFrame::IndefBytes { chunks }) => Ok(PyBytes::new(py, &chunks).into_any()),
+            Some(FeedFrame::IndefText { chunks }) => Ok(PyString::new(py, &chunks).into_any()),
+            _ => raise_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                "break marker found outside an indefinite-length item",
+            ),
+        }
+    }
+
+    /// Try to make one unit of progress: finish filling a pending byte/text string payload,
+    /// or parse and act on one item head. Returns `Ok(None)` if the buffer doesn't contain
+    /// enough data to make any progress right now; otherwise `Ok(Some(value))`, where `value`
+    /// is `Some(item)` if a top-level item just completed, or `None` if progress was made
+    /// but nothing has completed yet (e.g. a container was opened).
+    fn advance<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Option<Bound<'py, PyAny>>>> {
+        if let Some(pending) = self.pending.as_mut() {
+            let available = self.buffer.len() - self.pos;
+            if available == 0 {
+                return Ok(None);
+            }
+            let take = min(pending.remaining, available);
+            pending.data.extend_from_slice(&self.buffer[self.pos..self.pos + take]);
+            self.pos += take;
+            pending.remaining -= take;
+            if pending.remaining > 0 {
+                return Ok(None);
+            }
+            let pending = self.pending.take().unwrap();
+            let value = if pending.is_text {
+                self.decode_utf8(py, &pending.data)?
+            } else {
+                PyBytes::new(py, &pending.data).into_any()
+            };
+            return Ok(Some(self.complete(py, value)?));
+        }
+
+        let head = match try_parse_head(&self.buffer[self.pos..]) {
+            Ok(None) => return Ok(None),
+            Ok(Some(head)) => head,
+            Err(msg) => return raise_cbor_error(py, "CBORDecodeValueError", msg.as_str()),
+        };
+        let (major_type, subtype, value, header_len) = head;
+        self.pos += header_len;
+
+        match major_type {
+            0 => Ok(Some(self.complete(py, value.into_bound_py_any(py)?)?)),
+            1 => Ok(Some(self.complete(py, (-(value as i128) - 1).into_bound_py_any(py)?)?)),
+            2 | 3 => {
+                let is_text = major_type == 3;
+                if subtype == 31 {
+                    if is_text {
+                        self.push_frame(py, FeedFrame::IndefText { chunks: String::new() })?;
+                    } else {
+                        self.push_frame(py, FeedFrame::IndefBytes { chunks: Vec::new() })?;
+                    }
+                } else {
+                    self.pending = Some(PendingPayload {
+                        is_text,
+                        remaining: value as usize,
+                        data: Vec::new(),
+                    });
+                }
+                Ok(Some(None))
+            }
+            4 if subtype == 31 => {
+                self.push_frame(py, FeedFrame::IndefArray { items: Vec::new() })?;
+                Ok(Some(None))
+            }
+            4 if value == 0 => Ok(Some(self.complete(py, PyList::empty(py).into_any())?)),
+            4 => {
+                // Don't preallocate `items` from the declared length: it's attacker-controlled
+                // and arrives before a single item byte does. Let it grow one push() at a time
+                // as items actually complete, same as the indefinite-length array frame above.
+                self.push_frame(
+                    py,
+                    FeedFrame::Array { items: Vec::new(), remaining: value as usize },
+                )?;
+                Ok(Some(None))
+            }
+            5 if subtype == 31 => {
+                self.push_frame(py, FeedFrame::IndefMap { dict: PyDict::new(py).unbind(), pending_key: None })?;
+                Ok(Some(None))
+            }
+            5 if value == 0 => Ok(Some(self.complete(py, PyDict::new(py).into_any())?)),
+            5 => {
+                self.push_frame(
+                    py,
+                    FeedFrame::Map { dict: PyDict::new(py).unbind(), remaining: value as usize, pending_key: None },
+                )?;
+                Ok(Some(None))
+            }
+            6 => {
+                self.push_frame(py, FeedFrame::Tag { tagnum: value })?;
+                Ok(Some(None))
+            }
+            7 => match subtype {
+                ..20 => Ok(Some(self.complete(py, CBORSimpleValue::intern(py, subtype)?.into_bound_py_any(py)?)?)),
+                20 => Ok(Some(self.complete(py, false.into_bound_py_any(py)?)?)),
+                21 => Ok(Some(self.complete(py, true.into_bound_py_any(py)?)?)),
+                22 => Ok(Some(self.complete(py, py.None().into_bound_py_any(py)?)?)),
+                23 => Ok(Some(self.complete(py, UNDEFINED.get(py).unwrap().into_bound_py_any(py)?)?)),
+                24 => Ok(Some(self.complete(py, CBORSimpleValue::intern(py, value as u8)?.into_bound_py_any(py)?)?)),
+                25 => Ok(Some(self.complete(py, f16::from_bits(value as u16).to_f32().into_bound_py_any(py)?)?)),
+                26 => Ok(Some(self.complete(py, f32::from_bits(value as u32).into_bound_py_any(py)?)?)),
+                27 => Ok(Some(self.complete(py, f64::from_bits(value).into_bound_py_any(py)?)?)),
+                31 => {
+                    let closed = self.close_indefinite(py)?;
+                    Ok(Some(self.complete(py, closed)?))
+                }
+                _ => raise_cbor_error(
+                    py,
+                    "CBORDecodeValueError",
+                    format!("undefined reserved major type 7 subtype 0x{subtype:x}").as_str(),
+                ),
+            },
+            _ => unreachable!("major type is derived from a 3-bit field"),
+        }
+    }
+}
+
+#[pymethods]
+impl FeedDecoder {
+    #[new]
+    #[pyo3(signature = (*, max_depth: "int" = DEFAULT_MAX_DEPTH, str_errors: "str" = "strict"))]
+    fn new(py: Python<'_>, max_depth: usize, str_errors: &str) -> PyResult<Self> {
+        if !VALID_STR_ERRORS.contains(&str_errors) {
+            return raise_cbor_error(
+                py,
+                "CBORDecodeValueError",
+                format!("invalid str_errors value {str_errors:?}").as_str(),
+            );
+        }
+
+        Ok(Self {
+            buffer: Vec::new(),
+            pos: 0,
+            stack: Vec::new(),
+            pending: None,
+            max_depth,
+            str_errors: PyString::new(py, str_errors).unbind(),
+        })
+    }
+
+    /// Feed a new chunk of bytes into the decoder.
+    ///
+    /// :param bytes data: the next chunk of the CBOR byte stream
+    /// :return: the (possibly empty) list of top-level items that became complete as a
+    ///     result of adding ``data``
+    fn feed<'py>(&mut self, py: Python<'py>, data: &[u8]) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        self.buffer.extend_from_slice(data);
+        let mut completed = Vec::new();
+        while let Some(outcome) = self.advance(py)? {
+            if let Some(value) = outcome {
+                completed.push(value);
+            }
+        }
+
+        if self.pos > 0 {
+            self.buffer.drain(0..self.pos);
+            self.pos = 0;
+        }
+        Ok(completed)
+    }
+
+    /// `True` if the decoder is sitting at a clean item boundary -- no partially read
+    /// container or payload is waiting for more data.
+    #[getter]
+    fn at_item_boundary(&self) -> bool {
+        self.stack.is_empty() && self.pending.is_none()
+    }
+}
@@ -1,3 +1,4 @@
+use crate::_cbor2::ENCODERS;
 use crate::types::{BreakMarkerType, CBORSimpleValue, CBORTag, UndefinedType};
 use crate::utils::{PyImportable, raise_cbor_error};
 use bigdecimal::BigDecimal;
@@ -62,7 +63,8 @@ pub fn shareable_encoder<'py>(
 /// the class.
 ///
 /// When the class is constructed manually, the main entry points are :meth:`encode` and
-/// :meth:`encode_to_bytes`.
+/// :meth:`encode_to_bytes`, plus :meth:`encode_to_diagnostic` for rendering an object as
+/// :rfc:`8949` §8 Extended Diagnostic Notation instead of binary CBOR.
 ///
 /// :param ~typing.IO[bytes] fp:
 ///     the file to write to (any file-like object opened for writing in binary mode)
@@ -79,10 +81,16 @@ pub fn shareable_encoder<'py>(
 ///     line overhead
 /// :param encoders:
 ///     An optional mapping for overriding the encoding for select Python types.
-///     Each key in this mapping should be a Python type object, and the value a callable
-///     that takes two arguments: the encoder object and the object to encode.
+///     Each key in this mapping should be a Python type object, and the value either a
+///     callable that takes two arguments (the encoder object and the object to encode),
+///     or a declarative conversion descriptor. The only descriptor currently understood is
+///     ``("timestamp_fmt", format, tzinfo)``, which formats a :class:`~datetime.datetime`
+///     with the given :meth:`~datetime.datetime.strftime` pattern (coercing a naive value
+///     into ``tzinfo`` first, the same way ``timezone`` does) and emits it as a tagged
+///     (tag 0) text string, without invoking any Python callback.
 /// :type encoders: ~collections.abc.Mapping[type,
-///     ~collections.abc.Callable[[CBOREncoder, typing.Any], typing.Any]]
+///     ~collections.abc.Callable[[CBOREncoder, typing.Any], typing.Any]
+///     | tuple[str, str, datetime.tzinfo | None]]
 /// :param default:
 ///     a callable that is called by the encoder with two arguments (the encoder
 ///     instance and the value being encoded) when no suitable encoder has been found,
@@ -93,6 +101,14 @@ pub fn shareable_encoder<'py>(
 ///     when :data:`True`, use "canonical" CBOR representation; this typically involves
 ///     sorting maps, sets, etc. into a pre-determined order ensuring that
 ///     serializations are comparable without decoding
+/// :param bool deterministic:
+///     when :data:`True`, use :rfc:`8949` §4.2.1 "Core Deterministic Encoding"
+///     representation: map and set keys are sorted by the raw bytes of their own
+///     deterministically-encoded form, compared lexicographically with no length pre-sort
+///     (unlike ``canonical``, which still follows the older length-first RFC 7049 rule),
+///     integers and lengths are always encoded in their shortest form (already the default),
+///     floats are encoded in the shortest width that round-trips, and indefinite-length
+///     containers are rejected with :exc:`.CBOREncodeValueError`
 /// :param bool date_as_datetime:
 ///     set to :data:`True` to serialize date objects as datetimes (CBOR tag 0), which was
 ///     the default behavior in previous releases (cbor2 <= 4.1.2).
@@ -117,6 +133,9 @@ pub struct CBOREncoder {
     #[pyo3(get)]
     canonical: bool,
 
+    #[pyo3(get)]
+    deterministic: bool,
+
     #[pyo3(get)]
     date_as_datetime: bool,
 
@@ -141,7 +160,18 @@ pub struct CBOREncoder {
 const MAX_BUFFER_SIZE: usize = 4096;
 
 impl CBOREncoder {
+    /// Fall back to the global `_cbor2.encoders` registry (populated by module init and
+    /// extendable at runtime via `register_type_encoder`) when no per-instance `encoders`
+    /// mapping was supplied.
+    fn default_encoders(py: Python<'_>) -> Option<Py<PyMapping>> {
+        ENCODERS
+            .get(py)
+            .and_then(|d| d.bind(py).clone().into_any().cast_into::<PyMapping>().ok())
+            .map(Bound::unbind)
+    }
+
     pub fn new_internal(
+        py: Python<'_>,
         fp: Option<&Bound<'_, PyAny>>,
         datetime_as_timestamp: bool,
         timezone: Option<&Bound<'_, PyAny>>,
@@ -149,6 +179,7 @@ impl CBOREncoder {
         encoders: Option<&Bound<'_, PyMapping>>,
         default: Option<&Bound<'_, PyAny>>,
         canonical: bool,
+        deterministic: bool,
         date_as_datetime: bool,
         string_referencing: bool,
         indefinite_containers: bool,
@@ -160,11 +191,14 @@ impl CBOREncoder {
             value_sharing,
             default: None,
             canonical,
+            deterministic,
             date_as_datetime,
             string_referencing,
             string_namespacing: string_referencing,
             indefinite_containers,
-            encoders: encoders.map(|e| e.clone().unbind()),
+            encoders: encoders
+                .map(|e| e.clone().unbind())
+                .or_else(|| Self::default_encoders(py)),
             write_method: None,
             buffer: Vec::new(),
             shared_containers: HashMap::new(),
@@ -264,6 +298,15 @@ impl CBOREncoder {
         obj: &Bound<'_, PyAny>,
         f: impl FnOnce() -> PyResult<()>,
     ) -> PyResult<()> {
+        let this = slf.borrow();
+        if this.deterministic && this.indefinite_containers {
+            return raise_cbor_error(
+                slf.py(),
+                "CBOREncodeValueError",
+                "indefinite-length containers are not allowed in deterministic mode",
+            );
+        }
+        drop(this);
         if slf.borrow().string_namespacing {
             // Create a new string reference domain
             slf.borrow_mut().encode_length(slf.py(), 6, Some(256))?;
@@ -338,11 +381,13 @@ impl CBOREncoder {
         encoders = None,
         default = None,
         canonical = false,
+        deterministic = false,
         date_as_datetime = false,
         string_referencing = false,
         indefinite_containers = false
     ))]
     pub fn new(
+        py: Python<'_>,
         fp: &Bound<'_, PyAny>,
         datetime_as_timestamp: bool,
         timezone: Option<&Bound<'_, PyAny>>,
@@ -350,11 +395,13 @@ impl CBOREncoder {
         encoders: Option<&Bound<'_, PyMapping>>,
         default: Option<&Bound<'_, PyAny>>,
         canonical: bool,
+        deterministic: bool,
         date_as_datetime: bool,
         string_referencing: bool,
         indefinite_containers: bool,
     ) -> PyResult<Self> {
         CBOREncoder::new_internal(
+            py,
             Some(fp),
             datetime_as_timestamp,
             timezone,
@@ -362,6 +409,7 @@ impl CBOREncoder {
             encoders,
             default,
             canonical,
+            deterministic,
             date_as_datetime,
             string_referencing,
             indefinite_containers,
@@ -483,6 +531,20 @@ impl CBOREncoder {
             match encoders.bind(py).get_item(&obj.get_type()) {
                 Ok(encoder) => {
                     drop(this);
+                    if let Ok(descriptor) = encoder.cast::<PyTuple>()
+                        && descriptor.len() == 3
+                        && descriptor
+                            .get_item(0)?
+                            .cast::<PyString>()
+                            .is_ok_and(|kind| kind.to_string_lossy() == "timestamp_fmt")
+                    {
+                        return Self::encode_timestamp_fmt(
+                            slf,
+                            obj,
+                            &descriptor.get_item(1)?,
+                            &descriptor.get_item(2)?,
+                        );
+                    }
                     return encoder.call1((slf, obj)).map(|_| ());
                 }
                 Err(e) if e.is_instance_of::<PyLookupError>(py) => {}
@@ -546,6 +608,13 @@ impl CBOREncoder {
                             .unbind(),
                         CBOREncoder::encode_date,
                     ));
+                    encoders.push((
+                        py.import("datetime")?
+                            .getattr("timedelta")?
+                            .cast_into()?
+                            .unbind(),
+                        CBOREncoder::encode_timedelta,
+                    ));
                     encoders.push((
                         py.import("decimal")?
                             .getattr("Decimal")?
@@ -658,6 +727,22 @@ impl CBOREncoder {
         Ok(())
     }
 
+    /// Encode a CBOR sequence (:rfc:`8742`).
+    ///
+    /// Each item of ``objs`` is encoded in turn with no enclosing array and no break
+    /// marker, producing a concatenated CBOR sequence. Shared-reference and string-
+    /// reference state is independent per item, since :meth:`encode` already resets it
+    /// once each top-level item finishes.
+    ///
+    /// :param objs: an iterable of objects to serialize
+    #[pyo3(signature = (objs, /))]
+    pub fn encode_sequence(slf: &Bound<'_, Self>, objs: &Bound<'_, PyAny>) -> PyResult<()> {
+        for obj in objs.try_iter()? {
+            Self::encode(slf, &obj?)?;
+        }
+        Ok(())
+    }
+
     /// Encode the given object to a byte buffer and return its value as bytes.
     ///
     /// This method was intended to be used from the ``default`` hook when an
@@ -688,6 +773,273 @@ impl CBOREncoder {
         result.map(|_| buffer)
     }
 
+    /// Render the given object as :rfc:`8949` §8 Extended Diagnostic Notation (EDN).
+    ///
+    /// This reuses :meth:`encode_to_bytes` to serialize ``obj`` first, so the usual
+    /// type-dispatch logic in :meth:`encode` runs unchanged (datetimes, Decimals, tags,
+    /// shared references and so on are all encoded exactly as they would be for binary
+    /// output), then renders the resulting CBOR item(s) back out as diagnostic text.
+    /// Because value-sharing and string-referencing are themselves plain semantic tags
+    /// (28/29 and 256/25) in this encoder, they fall out of that rendering automatically.
+    ///
+    /// :param obj: the object to encode
+    /// :rtype: str
+    #[pyo3(signature = (obj, /))]
+    pub fn encode_to_diagnostic<'py>(
+        slf: &Bound<'py, Self>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<String> {
+        let data = Self::encode_to_bytes(slf, obj)?;
+        let mut cursor = 0usize;
+        Self::diagnostic_item(&data, &mut cursor)
+    }
+
+    //
+    // Diagnostic notation (:rfc:`8949` §8) rendering helpers
+    //
+    // These operate directly on already-encoded CBOR bytes (see `encode_to_diagnostic`)
+    // rather than on Python objects, since by this point all of the encoder's type
+    // dispatch and tag assignment has already happened.
+    //
+
+    fn diagnostic_read_byte(data: &[u8], cursor: &mut usize) -> PyResult<u8> {
+        let byte = *data.get(*cursor).ok_or_else(|| {
+            PyRuntimeError::new_err("truncated CBOR data while rendering diagnostic notation")
+        })?;
+        *cursor += 1;
+        Ok(byte)
+    }
+
+    fn diagnostic_read_bytes<'a>(
+        data: &'a [u8],
+        cursor: &mut usize,
+        len: usize,
+    ) -> PyResult<&'a [u8]> {
+        let end = cursor
+            .checked_add(len)
+            .filter(|&end| end <= data.len())
+            .ok_or_else(|| {
+                PyRuntimeError::new_err(
+                    "truncated CBOR data while rendering diagnostic notation",
+                )
+            })?;
+        let slice = &data[*cursor..end];
+        *cursor = end;
+        Ok(slice)
+    }
+
+    /// Returns `None` for an indefinite-length marker (additional info 31).
+    fn diagnostic_read_length(
+        data: &[u8],
+        cursor: &mut usize,
+        info: u8,
+    ) -> PyResult<Option<u64>> {
+        match info {
+            0..=23 => Ok(Some(info as u64)),
+            24 => Ok(Some(Self::diagnostic_read_byte(data, cursor)? as u64)),
+            25 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 2)?;
+                Ok(Some(u16::from_be_bytes(bytes.try_into().unwrap()) as u64))
+            }
+            26 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 4)?;
+                Ok(Some(u32::from_be_bytes(bytes.try_into().unwrap()) as u64))
+            }
+            27 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 8)?;
+                Ok(Some(u64::from_be_bytes(bytes.try_into().unwrap())))
+            }
+            31 => Ok(None),
+            _ => Err(PyRuntimeError::new_err(
+                "invalid CBOR additional information while rendering diagnostic notation",
+            )),
+        }
+    }
+
+    fn diagnostic_require_length(length: Option<u64>) -> PyResult<u64> {
+        length.ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "indefinite length is not valid for this major type in diagnostic notation",
+            )
+        })
+    }
+
+    /// Consumes and discards a following break marker (major 7, additional info 31) if
+    /// present, without consuming anything otherwise. Used to detect the end of an
+    /// indefinite-length byte/text string, array or map.
+    fn diagnostic_peek_is_break(data: &[u8], cursor: &mut usize) -> PyResult<bool> {
+        let byte = *data.get(*cursor).ok_or_else(|| {
+            PyRuntimeError::new_err("truncated CBOR data while rendering diagnostic notation")
+        })?;
+        if byte == 0xff {
+            *cursor += 1;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn diagnostic_item(data: &[u8], cursor: &mut usize) -> PyResult<String> {
+        let byte = Self::diagnostic_read_byte(data, cursor)?;
+        let major = byte >> 5;
+        let info = byte & 0x1f;
+
+        match major {
+            0 => {
+                let length = Self::diagnostic_read_length(data, cursor, info)?;
+                Ok(Self::diagnostic_require_length(length)?.to_string())
+            }
+            1 => {
+                let length = Self::diagnostic_require_length(
+                    Self::diagnostic_read_length(data, cursor, info)?,
+                )?;
+                Ok((-1_i128 - length as i128).to_string())
+            }
+            2 => Self::diagnostic_bytestring(data, cursor, info),
+            3 => Self::diagnostic_textstring(data, cursor, info),
+            4 => Self::diagnostic_array(data, cursor, info),
+            5 => Self::diagnostic_map(data, cursor, info),
+            6 => {
+                let tag = Self::diagnostic_require_length(
+                    Self::diagnostic_read_length(data, cursor, info)?,
+                )?;
+                let inner = Self::diagnostic_item(data, cursor)?;
+                Ok(format!("{tag}({inner})"))
+            }
+            7 => Self::diagnostic_simple_or_float(data, cursor, info),
+            _ => unreachable!("major type is masked to 3 bits"),
+        }
+    }
+
+    fn diagnostic_bytestring(data: &[u8], cursor: &mut usize, info: u8) -> PyResult<String> {
+        match Self::diagnostic_read_length(data, cursor, info)? {
+            Some(length) => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, length as usize)?;
+                let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                Ok(format!("h'{hex}'"))
+            }
+            None => {
+                let mut chunks = Vec::new();
+                while !Self::diagnostic_peek_is_break(data, cursor)? {
+                    chunks.push(Self::diagnostic_item(data, cursor)?);
+                }
+                Ok(format!("(_ {})", chunks.join(", ")))
+            }
+        }
+    }
+
+    fn diagnostic_textstring(data: &[u8], cursor: &mut usize, info: u8) -> PyResult<String> {
+        match Self::diagnostic_read_length(data, cursor, info)? {
+            Some(length) => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, length as usize)?;
+                let text = std::str::from_utf8(bytes).map_err(|_| {
+                    PyRuntimeError::new_err(
+                        "invalid UTF-8 while rendering diagnostic notation",
+                    )
+                })?;
+                Ok(format!(
+                    "\"{}\"",
+                    text.replace('\\', "\\\\").replace('"', "\\\"")
+                ))
+            }
+            None => {
+                let mut chunks = Vec::new();
+                while !Self::diagnostic_peek_is_break(data, cursor)? {
+                    chunks.push(Self::diagnostic_item(data, cursor)?);
+                }
+                Ok(format!("(_ {})", chunks.join(", ")))
+            }
+        }
+    }
+
+    fn diagnostic_array(data: &[u8], cursor: &mut usize, info: u8) -> PyResult<String> {
+        match Self::diagnostic_read_length(data, cursor, info)? {
+            Some(length) => {
+                let mut items = Vec::with_capacity(length as usize);
+                for _ in 0..length {
+                    items.push(Self::diagnostic_item(data, cursor)?);
+                }
+                Ok(format!("[{}]", items.join(", ")))
+            }
+            None => {
+                let mut items = Vec::new();
+                while !Self::diagnostic_peek_is_break(data, cursor)? {
+                    items.push(Self::diagnostic_item(data, cursor)?);
+                }
+                Ok(format!("[_ {}]", items.join(", ")))
+            }
+        }
+    }
+
+    fn diagnostic_map(data: &[u8], cursor: &mut usize, info: u8) -> PyResult<String> {
+        match Self::diagnostic_read_length(data, cursor, info)? {
+            Some(length) => {
+                let mut pairs = Vec::with_capacity(length as usize);
+                for _ in 0..length {
+                    let key = Self::diagnostic_item(data, cursor)?;
+                    let value = Self::diagnostic_item(data, cursor)?;
+                    pairs.push(format!("{key}: {value}"));
+                }
+                Ok(format!("{{{}}}", pairs.join(", ")))
+            }
+            None => {
+                let mut pairs = Vec::new();
+                while !Self::diagnostic_peek_is_break(data, cursor)? {
+                    let key = Self::diagnostic_item(data, cursor)?;
+                    let value = Self::diagnostic_item(data, cursor)?;
+                    pairs.push(format!("{key}: {value}"));
+                }
+                Ok(format!("{{_ {}}}", pairs.join(", ")))
+            }
+        }
+    }
+
+    fn diagnostic_simple_or_float(data: &[u8], cursor: &mut usize, info: u8) -> PyResult<String> {
+        match info {
+            20 => Ok("false".to_string()),
+            21 => Ok("true".to_string()),
+            22 => Ok("null".to_string()),
+            23 => Ok("undefined".to_string()),
+            24 => {
+                let value = Self::diagnostic_read_byte(data, cursor)?;
+                Ok(format!("simple({value})"))
+            }
+            25 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 2)?;
+                let value = f16::from_be_bytes(bytes.try_into().unwrap());
+                Ok(Self::diagnostic_format_float(value.to_f64()))
+            }
+            26 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 4)?;
+                let value = f32::from_be_bytes(bytes.try_into().unwrap());
+                Ok(Self::diagnostic_format_float(value as f64))
+            }
+            27 => {
+                let bytes = Self::diagnostic_read_bytes(data, cursor, 8)?;
+                let value = f64::from_be_bytes(bytes.try_into().unwrap());
+                Ok(Self::diagnostic_format_float(value))
+            }
+            0..=19 => Ok(format!("simple({info})")),
+            _ => Err(PyRuntimeError::new_err(
+                "unexpected break marker while rendering diagnostic notation",
+            )),
+        }
+    }
+
+    fn diagnostic_format_float(value: f64) -> String {
+        if value.is_nan() {
+            "NaN".to_string()
+        } else if value.is_infinite() {
+            if value > 0.0 {
+                "Infinity".to_string()
+            } else {
+                "-Infinity".to_string()
+            }
+        } else {
+            format!("{value}")
+        }
+    }
+
     /// Takes a key and calculates the length of its optimal byte
     /// representation, along with the representation itself.
     /// This is used as the sorting key in CBOR's canonical representations.
@@ -696,9 +1048,11 @@ impl CBOREncoder {
         key: &Bound<'py, PyAny>,
     ) -> PyResult<(usize, Bound<'py, PyAny>)> {
         Self::disable_string_referencing(slf, || {
-            let encoded = Self::encode_to_bytes(slf, &key)?;
-            let py_bytes = PyBytes::new(slf.py(), encoded.as_slice());
-            Ok((encoded.len(), py_bytes.into_any()))
+            Self::disable_value_sharing(slf, || {
+                let encoded = Self::encode_to_bytes(slf, &key)?;
+                let py_bytes = PyBytes::new(slf.py(), encoded.as_slice());
+                Ok((encoded.len(), py_bytes.into_any()))
+            })
         })
     }
 
@@ -716,6 +1070,35 @@ impl CBOREncoder {
         Self::encode_sortable_key(slf, &key)
     }
 
+    /// Takes a key and returns its deterministically-encoded bytes, used as the sort key for
+    /// :rfc:`8949` §4.2.1 Core Deterministic Encoding. Unlike `encode_sortable_key`, there's no
+    /// length pre-sort: :class:`bytes` comparison in Python is already bytewise lexicographic,
+    /// which is exactly the ordering the spec calls for.
+    fn encode_deterministic_sortable_key<'py>(
+        slf: &Bound<'py, Self>,
+        key: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        Self::disable_string_referencing(slf, || {
+            Self::disable_value_sharing(slf, || {
+                let encoded = Self::encode_to_bytes(slf, key)?;
+                Ok(PyBytes::new(slf.py(), encoded.as_slice()))
+            })
+        })
+    }
+
+    /// Takes a (key, value) tuple and returns its deterministically-encoded key bytes; see
+    /// `encode_deterministic_sortable_key`.
+    ///
+    /// :param item: a (key, value) tuple
+    /// :type item: tuple[Any, Any]
+    fn encode_deterministic_sortable_item<'py>(
+        slf: &Bound<'py, Self>,
+        item: &Bound<'py, PyTuple>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let key = item.get_item(0)?;
+        Self::encode_deterministic_sortable_key(slf, &key)
+    }
+
     fn encode_length(
         &mut self,
         py: Python<'_>,
@@ -832,7 +1215,16 @@ impl CBOREncoder {
             )?;
 
             let mut iterator = obj.call_method0("items")?.try_iter()?;
-            if slf.borrow().canonical {
+            if slf.borrow().deterministic {
+                // RFC 8949 Core Deterministic Encoding: sort purely by the bytewise
+                // lexicographic order of each key's own encoded bytes, with no length pre-sort.
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("key", slf.getattr("encode_deterministic_sortable_item")?)?;
+                iterator = SORTED_FUNC
+                    .get(py)?
+                    .call((iterator,), Some(&kwargs))?
+                    .try_iter()?;
+            } else if slf.borrow().canonical {
                 // Reorder keys according to Canonical CBOR specification where they're sorted
                 // by the length of the CBOR encoded value first, and only then by the lexical order
                 let kwargs = PyDict::new(py);
@@ -917,13 +1309,23 @@ impl CBOREncoder {
 
     fn encode_set(slf: &Bound<'_, Self>, obj: &Bound<'_, PySet>) -> PyResult<()> {
         // Semantic tag 258
-        if slf.borrow().canonical {
+        let this = slf.borrow();
+        if this.deterministic {
+            drop(this);
+            let py = slf.py();
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("key", slf.getattr("encode_deterministic_sortable_key")?)?;
+            let list = SORTED_FUNC.get(py)?.call((obj,), Some(&kwargs))?;
+            Self::encode_semantic(slf, 258, list.as_any())
+        } else if this.canonical {
+            drop(this);
             let py = slf.py();
             let kwargs = PyDict::new(py);
             kwargs.set_item("key", slf.getattr("encode_sortable_key")?)?;
             let list = SORTED_FUNC.get(py)?.call((obj,), Some(&kwargs))?;
             Self::encode_semantic(slf, 258, list.as_any())
         } else {
+            drop(this);
             let tuple = PyTuple::new(slf.py(), obj)?;
             Self::encode_semantic(slf, 258, tuple.as_any())
         }
@@ -931,13 +1333,23 @@ impl CBOREncoder {
 
     fn encode_frozenset(slf: &Bound<'_, Self>, obj: &Bound<'_, PyFrozenSet>) -> PyResult<()> {
         // Semantic tag 258
-        if slf.borrow().canonical {
+        let this = slf.borrow();
+        if this.deterministic {
+            drop(this);
+            let py = slf.py();
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("key", slf.getattr("encode_deterministic_sortable_key")?)?;
+            let list = SORTED_FUNC.get(py)?.call((obj,), Some(&kwargs))?;
+            Self::encode_semantic(slf, 258, list.as_any())
+        } else if this.canonical {
+            drop(this);
             let py = slf.py();
             let kwargs = PyDict::new(py);
             kwargs.set_item("key", slf.getattr("encode_sortable_key")?)?;
             let list = SORTED_FUNC.get(py)?.call((obj,), Some(&kwargs))?;
             Self::encode_semantic(slf, 258, list.as_any())
         } else {
+            drop(this);
             let tuple = PyTuple::new(slf.py(), obj)?;
             Self::encode_semantic(slf, 258, tuple.as_any())
         }
@@ -947,6 +1359,38 @@ impl CBOREncoder {
     // Semantic decoders (major tag 6)
     //
 
+    /// Encode a datetime using an explicit `("timestamp_fmt", format, tzinfo)` conversion
+    /// descriptor supplied via the `encoders` mapping, instead of a plain callable.
+    ///
+    /// `obj` is formatted with the strftime-style `format` string and emitted as a tagged
+    /// (tag 0) text string. A naive `obj` is coerced into `tzinfo` first, the same way the
+    /// default datetime encoder coerces naive datetimes via `timezone`/`set_timezone`.
+    fn encode_timestamp_fmt(
+        slf: &Bound<'_, Self>,
+        obj: &Bound<'_, PyAny>,
+        format: &Bound<'_, PyAny>,
+        tzinfo: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let py = slf.py();
+        let aware_datetime = if obj.getattr("tzinfo")?.is_none() {
+            if tzinfo.is_none() {
+                return raise_cbor_error(
+                    py,
+                    "CBOREncodeError",
+                    "naive datetime encountered and the timestamp_fmt conversion descriptor \
+                     did not specify a timezone",
+                );
+            }
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("tzinfo", tzinfo)?;
+            obj.call_method("replace", (), Some(&kwargs))?
+        } else {
+            obj.clone()
+        };
+        let formatted = aware_datetime.call_method1(intern!(py, "strftime"), (format,))?;
+        Self::encode_semantic(slf, 0, &formatted)
+    }
+
     fn encode_datetime(slf: &Bound<'_, Self>, obj: &Bound<'_, PyAny>) -> PyResult<()> {
         let py = slf.py();
 
@@ -1029,6 +1473,21 @@ impl CBOREncoder {
         }
     }
 
+    fn encode_timedelta(slf: &Bound<'_, Self>, obj: &Bound<'_, PyAny>) -> PyResult<()> {
+        // Semantic tag 1002 (:rfc:`9581`), encoded in its simpler registered form: a plain
+        // number of seconds rather than a map of components.
+        let py = slf.py();
+        let py_seconds = obj.call_method0(intern!(py, "total_seconds"))?;
+        let seconds_float: f64 = py_seconds.extract()?;
+        let seconds_int: i64 = seconds_float as i64;
+        let arg: Bound<'_, PyAny> = if seconds_int as f64 == seconds_float {
+            PyInt::new(py, seconds_int).into_any()
+        } else {
+            py_seconds
+        };
+        Self::encode_semantic(slf, 1002, &arg)
+    }
+
     fn encode_rational(slf: &Bound<'_, Self>, obj: &Bound<'_, PyAny>) -> PyResult<()> {
         // Semantic tag 30
         let numerator = obj.getattr("numerator")?;
@@ -1168,7 +1627,7 @@ impl CBOREncoder {
             let middle = if value.is_sign_positive() { 0x7c } else { 0xfc };
             slf.borrow_mut().fp_write(py, vec![0xf9, middle, 0x00])
         } else {
-            if slf.borrow().canonical {
+            if slf.borrow().canonical || slf.borrow().deterministic {
                 // Find the shortest form that did not lose precision with the cast
                 let value_32 = value as f32;
                 if value_32 as f64 == value {
@@ -9,9 +9,10 @@ use pyo3::prelude::pymodule;
 #[pymodule]
 mod _cbor2 {
     use pyo3::exceptions::PyValueError;
+    use pyo3::ffi;
     use pyo3::prelude::*;
     use pyo3::sync::PyOnceLock;
-    use pyo3::types::PyDict;
+    use pyo3::types::{PyBytes, PyCapsule, PyDict, PyType};
     use std::mem::take;
 
     #[pymodule_export]
@@ -23,6 +24,9 @@ mod _cbor2 {
     #[pymodule_export]
     use crate::decoder::CBORDecoder;
 
+    #[pymodule_export]
+    use crate::decoder::CBORSequenceIterator;
+
     #[pymodule_export]
     use crate::types::CBORTag;
 
@@ -32,15 +36,38 @@ mod _cbor2 {
     #[pymodule_export]
     use crate::types::FrozenDict;
 
+    #[pymodule_export]
+    use crate::decoder::CBORTokenIterator;
+
+    #[pymodule_export]
+    use crate::types::ArrayStartToken;
+
+    #[pymodule_export]
+    use crate::types::MapStartToken;
+
+    #[pymodule_export]
+    use crate::types::TagStartToken;
+
+    #[pymodule_export]
+    use crate::types::ContainerEndToken;
+
+    #[pymodule_export]
+    use crate::decoder::FeedDecoder;
+
     use crate::types::BreakMarkerType;
     use crate::types::UndefinedType;
 
     pub static ENCODERS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
     pub static MAJOR_DECODERS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
     pub static SEMANTIC_DECODERS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
+    /// A frozen snapshot of `SEMANTIC_DECODERS` as populated at module init, kept around so
+    /// `unregister_tag_decoder` can restore a tag's built-in handler after it's been
+    /// overridden, rather than merely deleting it.
+    static BUILTIN_SEMANTIC_DECODERS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
     pub static SYS_MAXSIZE: PyOnceLock<usize> = PyOnceLock::new();
     pub static UNDEFINED: PyOnceLock<Py<UndefinedType>> = PyOnceLock::new();
     pub static BREAK_MARKER: PyOnceLock<Py<BreakMarkerType>> = PyOnceLock::new();
+    pub static CONTAINER_END: PyOnceLock<Py<ContainerEndToken>> = PyOnceLock::new();
 
     ///  Deserialize an object from a bytestring.
     ///
@@ -58,6 +85,26 @@ mod _cbor2 {
     ///  :param str_errors:
     ///      determines how to handle unicode decoding errors (see the `Error Handlers`_
     ///      section in the standard library documentation for details)
+    ///  :param mime_policy:
+    ///      the :mod:`email.policy` to use when parsing tag 36 (MIME message) values; see
+    ///      :attr:`.CBORDecoder.mime_policy`
+    ///  :param record_offsets:
+    ///      set to ``True`` to have the decoder record the ``(start_offset, length)`` of every
+    ///      decoded node in the decoder's :attr:`~.CBORDecoder.offsets` mapping; since ``load``/``loads`` discard the decoder
+    ///      instance, use :class:`.CBORDecoder` directly to make use of this
+    ///  :param zero_copy:
+    ///      set to ``True`` to have fully buffered, definite-length byte strings returned as
+    ///      a :class:`memoryview` instead of a copied :class:`bytes` object
+    ///  :param canonical:
+    ///      set to ``True`` to reject input that isn't in deterministic/canonical CBOR form
+    ///      (non-shortest-form lengths or floats, indefinite-length strings/arrays/maps, or
+    ///      integer, bool, ``None``, byte string or text string map keys out of canonical
+    ///      byte-wise sorted order), raising
+    ///      :exc:`.CBORDecodeValueError`
+    ///  :param warn_on_noncanonical:
+    ///      set to ``True`` to have those same non-canonical conditions emit a
+    ///      :exc:`.CBORWarning` instead of being silently accepted; see
+    ///      :attr:`.CBORDecoder.warn_on_noncanonical`
     ///  :return:
     ///      the deserialized object
     ///
@@ -69,6 +116,11 @@ mod _cbor2 {
         tag_hook: "collections.abc.Callable | None" = None,
         object_hook: "collections.abc.Callable | None" = None,
         str_errors: "str" = "strict",
+        mime_policy: "str" = "compat32",
+        record_offsets: "bool" = false,
+        zero_copy: "bool" = false,
+        canonical: "bool" = false,
+        warn_on_noncanonical: "bool" = false,
     ))]
     fn load<'py>(
         py: Python<'py>,
@@ -76,8 +128,16 @@ mod _cbor2 {
         tag_hook: Option<&Bound<'py, PyAny>>,
         object_hook: Option<&Bound<'py, PyAny>>,
         str_errors: &str,
+        mime_policy: &str,
+        record_offsets: bool,
+        zero_copy: bool,
+        canonical: bool,
+        warn_on_noncanonical: bool,
     ) -> PyResult<Bound<'py, PyAny>> {
-        let decoder = CBORDecoder::new(py, fp, tag_hook, object_hook, str_errors, 4096)?;
+        let decoder = CBORDecoder::new(
+            py, fp, tag_hook, object_hook, str_errors, mime_policy, 4096, record_offsets,
+            zero_copy, canonical, warn_on_noncanonical, None, None, None, None,
+        )?;
         let instance = Bound::new(py, decoder)?;
         CBORDecoder::decode(&instance)
     }
@@ -98,6 +158,23 @@ mod _cbor2 {
     ///  :param str_errors:
     ///      determines how to handle unicode decoding errors (see the `Error Handlers`_
     ///      section in the standard library documentation for details)
+    ///  :param mime_policy:
+    ///      the :mod:`email.policy` to use when parsing tag 36 (MIME message) values; see
+    ///      :attr:`.CBORDecoder.mime_policy`
+    ///  :param record_offsets:
+    ///      set to ``True`` to have the decoder record the ``(start_offset, length)`` of every
+    ///      decoded node in the decoder's :attr:`~.CBORDecoder.offsets` mapping; since ``load``/``loads`` discard the decoder
+    ///      instance, use :class:`.CBORDecoder` directly to make use of this
+    ///  :param canonical:
+    ///      set to ``True`` to reject input that isn't in deterministic/canonical CBOR form
+    ///      (non-shortest-form lengths or floats, indefinite-length strings/arrays/maps, or
+    ///      integer, bool, ``None``, byte string or text string map keys out of canonical
+    ///      byte-wise sorted order), raising
+    ///      :exc:`.CBORDecodeValueError`
+    ///  :param warn_on_noncanonical:
+    ///      set to ``True`` to have those same non-canonical conditions emit a
+    ///      :exc:`.CBORWarning` instead of being silently accepted; see
+    ///      :attr:`.CBORDecoder.warn_on_noncanonical`
     ///  :return:
     ///      the deserialized object
     ///
@@ -108,7 +185,12 @@ mod _cbor2 {
         /, *,
         tag_hook: "collections.abc.Callable | None" = None,
         object_hook: "collections.abc.Callable | None" = None,
-        str_errors: "str" = "strict"
+        str_errors: "str" = "strict",
+        mime_policy: "str" = "compat32",
+        record_offsets: "bool" = false,
+        zero_copy: "bool" = false,
+        canonical: "bool" = false,
+        warn_on_noncanonical: "bool" = false
     ))]
     fn loads<'py>(
         py: Python<'py>,
@@ -116,13 +198,79 @@ mod _cbor2 {
         tag_hook: Option<&Bound<'py, PyAny>>,
         object_hook: Option<&Bound<'py, PyAny>>,
         str_errors: &str,
+        mime_policy: &str,
+        record_offsets: bool,
+        zero_copy: bool,
+        canonical: bool,
+        warn_on_noncanonical: bool,
     ) -> PyResult<Bound<'py, PyAny>> {
-        let decoder =
-            CBORDecoder::new_internal(py, None, data, tag_hook, object_hook, str_errors, 4096)?;
+        let decoder = CBORDecoder::new_internal(
+            py,
+            None,
+            data,
+            tag_hook,
+            object_hook,
+            str_errors,
+            mime_policy,
+            4096,
+            record_offsets,
+            zero_copy,
+            canonical,
+            warn_on_noncanonical,
+            None,
+            None,
+            None,
+            None,
+        )?;
         let instance = Bound::new(py, decoder)?;
         CBORDecoder::decode(&instance)
     }
 
+    ///  Decode a CBOR sequence (:rfc:`8742`) from a file as a lazy iterator.
+    ///
+    ///  :param fp:
+    ///      the file to read from (any file-like object opened for reading in binary mode)
+    ///  :param tag_hook:
+    ///      callable that takes 2 arguments: the decoder instance, and the :class:`.CBORTag`
+    ///      to be decoded. This callback is invoked for any tags for which there is no
+    ///      specific decoder. The return value is substituted for the :class:`.CBORTag`
+    ///      object in the deserialized output
+    ///  :param object_hook:
+    ///      callable that takes 2 arguments: the decoder instance, and a dictionary. This
+    ///      callback is invoked for each deserialized :class:`dict` object. The return value
+    ///      is substituted for the dict in the deserialized output.
+    ///  :param str_errors:
+    ///      determines how to handle unicode decoding errors (see the `Error Handlers`_
+    ///      section in the standard library documentation for details)
+    ///  :return:
+    ///      an iterator that yields one decoded object per top-level CBOR item, until
+    ///      ``fp`` is exhausted
+    ///
+    ///  .. _Error Handlers: https://docs.python.org/3/library/codecs.html#error-handlers
+    #[pyfunction]
+    #[pyo3(signature = (
+        fp: "typing.IO[bytes]",
+        /, *,
+        tag_hook: "collections.abc.Callable | None" = None,
+        object_hook: "collections.abc.Callable | None" = None,
+        str_errors: "str" = "strict",
+    ))]
+    fn load_sequence(
+        py: Python<'_>,
+        fp: &Bound<'_, PyAny>,
+        tag_hook: Option<&Bound<'_, PyAny>>,
+        object_hook: Option<&Bound<'_, PyAny>>,
+        str_errors: &str,
+    ) -> PyResult<Py<crate::decoder::CBORSequenceIterator>> {
+        let decoder =
+            CBORDecoder::new(
+                py, fp, tag_hook, object_hook, str_errors, "compat32", 4096, false, false, false,
+                None, None, None, None,
+            )?;
+        let instance = Bound::new(py, decoder)?;
+        CBORDecoder::decode_sequence(&instance)
+    }
+
     ///  Serialize an object to a file.
     ///
     ///  :param fp:
@@ -149,6 +297,9 @@ mod _cbor2 {
     ///      when ``True``, use "canonical" CBOR representation; this typically involves
     ///      sorting maps, sets, etc. into a pre-determined order ensuring that
     ///      serializations are comparable without decoding
+    ///  :param deterministic:
+    ///      when ``True``, use :rfc:`8949` §4.2.1 "Core Deterministic Encoding"
+    ///      representation; see :attr:`.CBOREncoder.deterministic`
     ///  :param date_as_datetime:
     ///      set to ``True`` to serialize date objects as datetimes (CBOR tag 0), which was
     ///      the default behavior in previous releases (cbor2 <= 4.1.2).
@@ -168,6 +319,7 @@ mod _cbor2 {
         value_sharing: "bool" = false,
         default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
         canonical: "bool" = false,
+        deterministic: "bool" = false,
         date_as_datetime: "bool" = false,
         string_referencing: "bool" = false,
         indefinite_containers: "bool" = false
@@ -181,6 +333,7 @@ mod _cbor2 {
         value_sharing: bool,
         default: Option<&Bound<'py, PyAny>>,
         canonical: bool,
+        deterministic: bool,
         date_as_datetime: bool,
         string_referencing: bool,
         indefinite_containers: bool,
@@ -193,6 +346,7 @@ mod _cbor2 {
             value_sharing,
             default,
             canonical,
+            deterministic,
             date_as_datetime,
             string_referencing,
             indefinite_containers,
@@ -225,6 +379,9 @@ mod _cbor2 {
     ///      when ``True``, use "canonical" CBOR representation; this typically involves
     ///      sorting maps, sets, etc. into a pre-determined order ensuring that
     ///      serializations are comparable without decoding
+    ///  :param deterministic:
+    ///      when ``True``, use :rfc:`8949` §4.2.1 "Core Deterministic Encoding"
+    ///      representation; see :attr:`.CBOREncoder.deterministic`
     ///  :param date_as_datetime:
     ///      set to ``True`` to serialize date objects as datetimes (CBOR tag 0), which was
     ///      the default behavior in previous releases (cbor2 <= 4.1.2).
@@ -242,6 +399,7 @@ mod _cbor2 {
         value_sharing: "bool" = false,
         default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
         canonical: "bool" = false,
+        deterministic: "bool" = false,
         date_as_datetime: "bool" = false,
         string_referencing: "bool" = false,
         indefinite_containers: "bool" = false
@@ -254,6 +412,7 @@ mod _cbor2 {
         value_sharing: bool,
         default: Option<&Bound<'_, PyAny>>,
         canonical: bool,
+        deterministic: bool,
         date_as_datetime: bool,
         string_referencing: bool,
         indefinite_containers: bool,
@@ -266,6 +425,7 @@ mod _cbor2 {
             value_sharing,
             default,
             canonical,
+            deterministic,
             date_as_datetime,
             string_referencing,
             indefinite_containers,
@@ -275,6 +435,410 @@ mod _cbor2 {
         Ok(take(&mut instance.borrow_mut().buffer))
     }
 
+    ///  Encode a CBOR sequence (:rfc:`8742`) to a file.
+    ///
+    ///  :param objs:
+    ///      an iterable of objects to serialize
+    ///  :param fp:
+    ///      the file to write to (any file-like object opened for writing in binary mode)
+    ///  :param datetime_as_timestamp:
+    ///      set to ``True`` to serialize datetimes as UNIX timestamps (this makes datetimes
+    ///      more concise on the wire, but loses the timezone information)
+    ///  :param timezone:
+    ///      the default timezone to use for serializing naive datetimes; if this is not
+    ///      specified naive datetimes will throw a :exc:`ValueError` when encoding is
+    ///      attempted
+    ///  :param value_sharing:
+    ///      set to ``True`` to allow more efficient serializing of repeated values
+    ///      and, more importantly, cyclic data structures, at the cost of extra
+    ///      line overhead
+    ///  :param default:
+    ///      a callable that is called by the encoder with two arguments (the encoder
+    ///      instance and the value being encoded) when no suitable encoder has been found,
+    ///      and should use the methods on the encoder to encode any objects it wants to add
+    ///      to the data stream
+    ///  :param canonical:
+    ///      when ``True``, use "canonical" CBOR representation; this typically involves
+    ///      sorting maps, sets, etc. into a pre-determined order ensuring that
+    ///      serializations are comparable without decoding
+    ///  :param deterministic:
+    ///      when ``True``, use :rfc:`8949` §4.2.1 "Core Deterministic Encoding"
+    ///      representation; see :attr:`.CBOREncoder.deterministic`
+    ///  :param date_as_datetime:
+    ///      set to ``True`` to serialize date objects as datetimes (CBOR tag 0), which was
+    ///      the default behavior in previous releases (cbor2 <= 4.1.2).
+    ///  :param string_referencing:
+    ///      set to ``True`` to allow more efficient serializing of repeated string values
+    ///  :param indefinite_containers:
+    ///      encode containers as indefinite (use stop code instead of specifying length)
+    #[pyfunction]
+    #[pyo3(signature = (
+        objs,
+        /,
+        fp: "typing.IO[bytes]",
+        *,
+        datetime_as_timestamp: "bool" = false,
+        timezone: "datetime.tzinfo | None" = None,
+        value_sharing: "bool" = false,
+        default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
+        canonical: "bool" = false,
+        deterministic: "bool" = false,
+        date_as_datetime: "bool" = false,
+        string_referencing: "bool" = false,
+        indefinite_containers: "bool" = false
+    ))]
+    fn dump_many<'py>(
+        py: Python<'py>,
+        objs: &Bound<'py, PyAny>,
+        fp: &Bound<'py, PyAny>,
+        datetime_as_timestamp: bool,
+        timezone: Option<&Bound<'py, PyAny>>,
+        value_sharing: bool,
+        default: Option<&Bound<'py, PyAny>>,
+        canonical: bool,
+        deterministic: bool,
+        date_as_datetime: bool,
+        string_referencing: bool,
+        indefinite_containers: bool,
+    ) -> PyResult<()> {
+        let encoder = CBOREncoder::new(
+            py,
+            Some(fp),
+            datetime_as_timestamp,
+            timezone,
+            value_sharing,
+            default,
+            canonical,
+            deterministic,
+            date_as_datetime,
+            string_referencing,
+            indefinite_containers,
+        )?;
+        let instance = Bound::new(py, encoder)?;
+        CBOREncoder::encode_sequence(&instance, objs)
+    }
+
+    ///  Encode a CBOR sequence (:rfc:`8742`) to a bytestring.
+    ///
+    ///  :param objs:
+    ///      an iterable of objects to serialize
+    ///  :param datetime_as_timestamp:
+    ///      set to ``True`` to serialize datetimes as UNIX timestamps (this makes datetimes
+    ///      more concise on the wire, but loses the timezone information)
+    ///  :param timezone:
+    ///      the default timezone to use for serializing naive datetimes; if this is not
+    ///      specified naive datetimes will throw a :exc:`ValueError` when encoding is
+    ///      attempted
+    ///  :param value_sharing:
+    ///      set to ``True`` to allow more efficient serializing of repeated values
+    ///      and, more importantly, cyclic data structures, at the cost of extra
+    ///      line overhead
+    ///  :param default:
+    ///      a callable that is called by the encoder with two arguments (the encoder
+    ///      instance and the value being encoded) when no suitable encoder has been found,
+    ///      and should use the methods on the encoder to encode any objects it wants to add
+    ///      to the data stream
+    ///  :param canonical:
+    ///      when ``True``, use "canonical" CBOR representation; this typically involves
+    ///      sorting maps, sets, etc. into a pre-determined order ensuring that
+    ///      serializations are comparable without decoding
+    ///  :param deterministic:
+    ///      when ``True``, use :rfc:`8949` §4.2.1 "Core Deterministic Encoding"
+    ///      representation; see :attr:`.CBOREncoder.deterministic`
+    ///  :param date_as_datetime:
+    ///      set to ``True`` to serialize date objects as datetimes (CBOR tag 0), which was
+    ///      the default behavior in previous releases (cbor2 <= 4.1.2).
+    ///  :param string_referencing:
+    ///      set to ``True`` to allow more efficient serializing of repeated string values
+    ///  :param indefinite_containers:
+    ///      encode containers as indefinite (use stop code instead of specifying length)
+    ///  :return: the serialized output
+    #[pyfunction]
+    #[pyo3(signature = (
+        objs,
+        /, *,
+        datetime_as_timestamp: "bool" = false,
+        timezone: "datetime.tzinfo | None" = None,
+        value_sharing: "bool" = false,
+        default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
+        canonical: "bool" = false,
+        deterministic: "bool" = false,
+        date_as_datetime: "bool" = false,
+        string_referencing: "bool" = false,
+        indefinite_containers: "bool" = false
+    ))]
+    fn dumps_many<'py>(
+        py: Python<'py>,
+        objs: &Bound<'py, PyAny>,
+        datetime_as_timestamp: bool,
+        timezone: Option<&Bound<'py, PyAny>>,
+        value_sharing: bool,
+        default: Option<&Bound<'py, PyAny>>,
+        canonical: bool,
+        deterministic: bool,
+        date_as_datetime: bool,
+        string_referencing: bool,
+        indefinite_containers: bool,
+    ) -> PyResult<Vec<u8>> {
+        let encoder = CBOREncoder::new(
+            py,
+            None,
+            datetime_as_timestamp,
+            timezone,
+            value_sharing,
+            default,
+            canonical,
+            deterministic,
+            date_as_datetime,
+            string_referencing,
+            indefinite_containers,
+        )?;
+        let instance = Bound::new(py, encoder)?;
+        CBOREncoder::encode_sequence(&instance, objs)?;
+        Ok(take(&mut instance.borrow_mut().buffer))
+    }
+
+    ///  Encode a CBOR sequence (:rfc:`8742`) to a file-like object.
+    ///
+    ///  This is an alias of :func:`dump_many` using the :rfc:`8742` terminology.
+    ///
+    ///  :param objs:
+    ///      an iterable of objects to serialize
+    ///  :param fp:
+    ///      a writable file-like object
+    #[pyfunction]
+    #[pyo3(signature = (
+        objs,
+        /,
+        fp: "typing.IO[bytes]",
+        *,
+        datetime_as_timestamp: "bool" = false,
+        timezone: "datetime.tzinfo | None" = None,
+        value_sharing: "bool" = false,
+        default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
+        canonical: "bool" = false,
+        deterministic: "bool" = false,
+        date_as_datetime: "bool" = false,
+        string_referencing: "bool" = false,
+        indefinite_containers: "bool" = false
+    ))]
+    fn dump_sequence<'py>(
+        py: Python<'py>,
+        objs: &Bound<'py, PyAny>,
+        fp: &Bound<'py, PyAny>,
+        datetime_as_timestamp: bool,
+        timezone: Option<&Bound<'py, PyAny>>,
+        value_sharing: bool,
+        default: Option<&Bound<'py, PyAny>>,
+        canonical: bool,
+        deterministic: bool,
+        date_as_datetime: bool,
+        string_referencing: bool,
+        indefinite_containers: bool,
+    ) -> PyResult<()> {
+        dump_many(
+            py,
+            objs,
+            fp,
+            datetime_as_timestamp,
+            timezone,
+            value_sharing,
+            default,
+            canonical,
+            deterministic,
+            date_as_datetime,
+            string_referencing,
+            indefinite_containers,
+        )
+    }
+
+    ///  Encode a CBOR sequence (:rfc:`8742`) to a bytestring.
+    ///
+    ///  This is an alias of :func:`dumps_many` using the :rfc:`8742` terminology.
+    ///
+    ///  :param objs:
+    ///      an iterable of objects to serialize
+    ///  :return: the serialized output
+    #[pyfunction]
+    #[pyo3(signature = (
+        objs,
+        /, *,
+        datetime_as_timestamp: "bool" = false,
+        timezone: "datetime.tzinfo | None" = None,
+        value_sharing: "bool" = false,
+        default: "collections.abc.Callable[[CBOREncoder, typing.Any], None] | None" = None,
+        canonical: "bool" = false,
+        deterministic: "bool" = false,
+        date_as_datetime: "bool" = false,
+        string_referencing: "bool" = false,
+        indefinite_containers: "bool" = false
+    ))]
+    fn dumps_sequence<'py>(
+        py: Python<'py>,
+        objs: &Bound<'py, PyAny>,
+        datetime_as_timestamp: bool,
+        timezone: Option<&Bound<'py, PyAny>>,
+        value_sharing: bool,
+        default: Option<&Bound<'py, PyAny>>,
+        canonical: bool,
+        deterministic: bool,
+        date_as_datetime: bool,
+        string_referencing: bool,
+        indefinite_containers: bool,
+    ) -> PyResult<Vec<u8>> {
+        dumps_many(
+            py,
+            objs,
+            datetime_as_timestamp,
+            timezone,
+            value_sharing,
+            default,
+            canonical,
+            deterministic,
+            date_as_datetime,
+            string_referencing,
+            indefinite_containers,
+        )
+    }
+
+    ///  Register a decoder callback for a CBOR semantic tag, for every decoder created from
+    ///  this point on.
+    ///
+    ///  This populates the same ``semantic_decoders`` mapping consulted by
+    ///  :meth:`CBORDecoder.decode_semantic` before it falls back to the built-in tag
+    ///  decoders and, failing that, ``tag_hook``. It lets a library ship a reusable codec for
+    ///  a domain-specific tag once, instead of wiring a ``tag_hook`` by hand on every decoder.
+    ///  Takes precedence over any built-in decoder registered for the same tag; see
+    ///  :func:`unregister_tag_decoder` to undo this.
+    ///
+    ///  :param tag:
+    ///      the semantic tag number to handle
+    ///  :param decoder:
+    ///      a callable that takes the :class:`.CBORDecoder` instance and returns the decoded
+    ///      value, in the same manner as the decoder's own ``decode_*`` methods
+    #[pyfunction]
+    fn register_tag_decoder(py: Python<'_>, tag: u64, decoder: &Bound<'_, PyAny>) -> PyResult<()> {
+        SEMANTIC_DECODERS
+            .get(py)
+            .unwrap()
+            .bind(py)
+            .set_item(tag, decoder)
+    }
+
+    ///  Undo a previous :func:`register_tag_decoder` call for a semantic tag, for every
+    ///  decoder created from this point on.
+    ///
+    ///  Restores the built-in decoder for ``tag``, if one exists; otherwise the tag reverts to
+    ///  having no registered decoder at all, so it falls through to ``tag_hook`` like any other
+    ///  tag without a built-in handler.
+    ///
+    ///  :param tag:
+    ///      the semantic tag number to stop overriding
+    #[pyfunction]
+    fn unregister_tag_decoder(py: Python<'_>, tag: u64) -> PyResult<()> {
+        let semantic_decoders = SEMANTIC_DECODERS.get(py).unwrap().bind(py);
+        let builtins = BUILTIN_SEMANTIC_DECODERS.get(py).unwrap().bind(py);
+        match builtins.get_item(tag)? {
+            Some(builtin_decoder) => semantic_decoders.set_item(tag, builtin_decoder),
+            None => match semantic_decoders.del_item(tag) {
+                Ok(()) => Ok(()),
+                Err(e) if e.is_instance_of::<pyo3::exceptions::PyKeyError>(py) => Ok(()),
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    ///  Register an encoder callback for a Python type, for every encoder created from this
+    ///  point on.
+    ///
+    ///  This populates the same ``encoders`` mapping consulted by
+    ///  :meth:`CBOREncoder.encode` before it falls back to the built-in type encoders and,
+    ///  failing that, ``default``. It lets a library ship a reusable codec for a domain type
+    ///  once, instead of wiring a ``default`` callback by hand on every encoder.
+    ///
+    ///  :param py_type:
+    ///      the Python type to handle
+    ///  :param encoder:
+    ///      a callable that takes the :class:`.CBOREncoder` instance and the value to encode,
+    ///      and uses the encoder's methods to write the CBOR representation
+    #[pyfunction]
+    fn register_type_encoder(
+        py: Python<'_>,
+        py_type: &Bound<'_, PyType>,
+        encoder: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        ENCODERS
+            .get(py)
+            .unwrap()
+            .bind(py)
+            .set_item(py_type, encoder)
+    }
+
+    // Stable C ABI, published as the `cbor2._cbor2._C_API` capsule (see `register_c_api`,
+    // called from module init below) so other native extensions can call into cbor2
+    // directly, without round-tripping through the Python-level `loads`/`dumps` attribute
+    // lookups and argument tuple boxing. Mirrors the `encode`/`decode` defaults of
+    // `dumps`/`loads`; callers wanting non-default options should keep using the Python API.
+    // A matching C header declaring this struct and an `import_cbor2()` helper ships
+    // alongside the extension, following the same pattern as the C APIs exposed by
+    // `array`/`datetime`.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct CApi {
+        encode: unsafe extern "C" fn(obj: *mut ffi::PyObject) -> *mut ffi::PyObject,
+        decode:
+            unsafe extern "C" fn(data: *const u8, len: ffi::Py_ssize_t) -> *mut ffi::PyObject,
+    }
+
+    unsafe extern "C" fn c_api_encode(obj: *mut ffi::PyObject) -> *mut ffi::PyObject {
+        Python::with_gil(|py| {
+            // SAFETY: the caller must pass a valid, borrowed `PyObject*` while holding the
+            // GIL, per the contract documented in the shipped C header.
+            let obj = unsafe { Bound::from_borrowed_ptr(py, obj) };
+            match dumps(
+                py, &obj, false, None, false, None, false, false, false, false, false,
+            ) {
+                Ok(bytes) => PyBytes::new(py, &bytes).into_ptr(),
+                Err(e) => {
+                    e.restore(py);
+                    std::ptr::null_mut()
+                }
+            }
+        })
+    }
+
+    unsafe extern "C" fn c_api_decode(
+        data: *const u8,
+        len: ffi::Py_ssize_t,
+    ) -> *mut ffi::PyObject {
+        Python::with_gil(|py| {
+            // SAFETY: the caller must guarantee that `data` points to at least `len`
+            // readable bytes, per the contract documented in the shipped C header.
+            let slice = unsafe { std::slice::from_raw_parts(data, len.max(0) as usize) };
+            match loads(
+                py, slice.to_vec(), None, None, "strict", "compat32", false, false, false, false,
+            ) {
+                Ok(value) => value.into_ptr(),
+                Err(e) => {
+                    e.restore(py);
+                    std::ptr::null_mut()
+                }
+            }
+        })
+    }
+
+    const C_API: CApi = CApi {
+        encode: c_api_encode,
+        decode: c_api_decode,
+    };
+
+    fn register_c_api(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        let py = m.py();
+        let name = std::ffi::CString::from(c"cbor2._cbor2._C_API");
+        let capsule = PyCapsule::new(py, C_API, Some(name))?;
+        m.add("_C_API", capsule)
+    }
+
     #[pymodule_init]
     fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
         // Register cbor2.FrozenDict as a Mapping subclass
@@ -369,6 +933,7 @@ mod _cbor2 {
         register_semantic_decoder(43000, "decode_complex")?;
         register_semantic_decoder(55799, "decode_self_describe_cbor")?;
         m.add("semantic_decoders", semantic_decoders.clone())?;
+        BUILTIN_SEMANTIC_DECODERS.get_or_init(py, || semantic_decoders.copy().unwrap().unbind());
         SEMANTIC_DECODERS.get_or_init(py, || semantic_decoders.unbind());
 
         let undefined = Bound::new(py, UndefinedType)?;
@@ -379,8 +944,14 @@ mod _cbor2 {
         m.add("break_marker", break_marker.clone())?;
         BREAK_MARKER.get_or_init(py, || break_marker.unbind());
 
+        let container_end = Bound::new(py, ContainerEndToken)?;
+        m.add("container_end", container_end.clone())?;
+        CONTAINER_END.get_or_init(py, || container_end.unbind());
+
         SYS_MAXSIZE.get_or_try_init(py, || py.import("sys")?.getattr("maxsize")?.extract())?;
 
+        register_c_api(m)?;
+
         Ok(())
     }
 }
@@ -3,13 +3,27 @@ use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::PyAnyMethods;
 use pyo3::types::{
     PyDict, PyDictMethods, PyFrozenSet, PyInt, PyIterator, PyNotImplemented, PyString,
-    PyTuple,
+    PyTuple, PyType,
 };
+use pyo3::sync::PyOnceLock;
 use pyo3::{
     pyclass, pymethods, Bound, IntoPyObjectExt, Py, PyAny, PyResult, PyTypeInfo, Python,
 };
+use std::cell::RefCell;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+// Fixed marker hashed in place of a value that recurses back into a `CBORTag` that is
+// already being hashed, so self-referential structures (CBOR shared-reference tags 28/29)
+// are hashable instead of recursing forever.
+const CYCLE_HASH_SENTINEL: u64 = 0x63_62_6f_72_32_5f_5f_21; // "cbor2__!" as bytes
+
+thread_local! {
+    static HASH_STACK: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+    static CMP_STACK: RefCell<HashSet<(usize, usize)>> = RefCell::new(HashSet::new());
+}
 
 /// Represents a CBOR semantic tag.
 ///
@@ -38,38 +52,68 @@ impl CBORTag {
     }
 
     fn __richcmp__<'py>(
-        &self,
-        py: Python<'py>,
+        slf: &Bound<'py, Self>,
         other: &Bound<'py, PyAny>,
         op: CompareOp,
     ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
         if let Ok(other) = other.cast::<CBORTag>() {
+            let self_tag = slf.borrow().tag;
             let other_tag = other.borrow().tag;
-            if self.tag != other_tag {
-                return op.matches(self.tag.cmp(&other_tag)).into_bound_py_any(py)
+            if self_tag != other_tag {
+                return op.matches(self_tag.cmp(&other_tag)).into_bound_py_any(py)
+            }
+
+            // Guard against infinite recursion when the tag's value (directly or
+            // transitively) refers back to this same pair of objects, as happens when
+            // decoding CBOR shared-reference tags (28/29).
+            let edge = (slf.as_ptr() as usize, other.as_ptr() as usize);
+            let already_visiting = CMP_STACK.with(|stack| !stack.borrow_mut().insert(edge));
+            if already_visiting {
+                // Treat a repeated back-edge at a structurally matching position as equal.
+                return op.matches(std::cmp::Ordering::Equal).into_bound_py_any(py);
             }
-            let borrowed_other = other.borrow();
-            let bound_self = self.value.bind(py);
-            let bound_other = borrowed_other.value.bind(py);
-            let compare_result = match op {
-                CompareOp::Eq => bound_self.eq(bound_other),
-                CompareOp::Ne => bound_self.ne(bound_other),
-                CompareOp::Lt => bound_self.lt(bound_other),
-                CompareOp::Le => bound_self.le(bound_other),
-                CompareOp::Gt => bound_self.gt(bound_other),
-                CompareOp::Ge => bound_self.ge(bound_other),
-            }?;
-            compare_result.into_bound_py_any(py)
+
+            let bound_self = slf.borrow().value.clone_ref(py);
+            let bound_other = other.borrow().value.clone_ref(py);
+            let compare_result = (|| {
+                let bound_self = bound_self.bind(py);
+                let bound_other = bound_other.bind(py);
+                match op {
+                    CompareOp::Eq => bound_self.eq(bound_other),
+                    CompareOp::Ne => bound_self.ne(bound_other),
+                    CompareOp::Lt => bound_self.lt(bound_other),
+                    CompareOp::Le => bound_self.le(bound_other),
+                    CompareOp::Gt => bound_self.gt(bound_other),
+                    CompareOp::Ge => bound_self.ge(bound_other),
+                }
+            })();
+            CMP_STACK.with(|stack| {
+                stack.borrow_mut().remove(&edge);
+            });
+            compare_result?.into_bound_py_any(py)
         } else {
             // Non-comparable types: signal NotImplemented to Python
             PyNotImplemented::get(py).into_bound_py_any(py)
         }
     }
 
-    fn __hash__(&self, py: Python<'_>) -> PyResult<u64> {
+    fn __hash__(slf: &Bound<'_, Self>) -> PyResult<u64> {
+        let py = slf.py();
+
+        // Guard against infinite recursion when the value (directly or transitively)
+        // contains this same tag, as happens when decoding CBOR shared-reference tags
+        // (28/29).
+        let self_id = slf.as_ptr() as usize;
+        let already_hashing = HASH_STACK.with(|stack| !stack.borrow_mut().insert(self_id));
+        if already_hashing {
+            return Ok(CYCLE_HASH_SENTINEL);
+        }
+
+        let this = slf.borrow();
         let mut hasher = DefaultHasher::new();
-        hasher.write_u64(self.tag);
-        match self.value.call_method0(py, "__hash__") {
+        hasher.write_u64(this.tag);
+        let result = match this.value.call_method0(py, "__hash__") {
             Ok(value_hash) => {
                 hasher.write_isize(value_hash.extract(py)?);
                 Ok(hasher.finish())
@@ -79,7 +123,11 @@ impl CBORTag {
                 exc.set_cause(py, Some(cause));
                 Err(exc)
             }
-        }
+        };
+        HASH_STACK.with(|stack| {
+            stack.borrow_mut().remove(&self_id);
+        });
+        result
     }
 
     fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
@@ -89,23 +137,54 @@ impl CBORTag {
             self.value.bind(py).repr()?
         ))
     }
+
+    /// Support pickling and ``copy.copy``/``copy.deepcopy``.
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let cls = py.get_type::<CBORTag>();
+        let args = PyTuple::new(py, [self.tag.into_bound_py_any(py)?, self.value.bind(py).clone()])?;
+        PyTuple::new(py, [cls.into_any(), args.into_any()])
+    }
 }
 
 /// Represents a CBOR "simple value".
 ///
+/// Instances are interned: constructing two simple values with the same numeric value
+/// returns the same object, and the value behaves like an :class:`int` (it supports
+/// ``__int__``/``__index__``) while retaining its distinct CBOR type.
+///
 /// :param int value: the value (0-255)
 #[pyclass(frozen, str = "{0}", module = "cbor2")]
 #[derive(PartialEq, PartialOrd, Hash)]
 pub struct CBORSimpleValue(pub u8);
 
+// Flyweight cache of the (at most ~248) valid `CBORSimpleValue` instances, so that repeated
+// simple values decoded from (or constructed for) the same CBOR stream share one Python
+// object instead of allocating a new one every time.
+static SIMPLE_VALUE_CACHE: PyOnceLock<Mutex<HashMap<u8, Py<CBORSimpleValue>>>> = PyOnceLock::new();
+
+impl CBORSimpleValue {
+    /// Return the interned instance for `value`, creating and caching it on first use.
+    pub fn intern(py: Python<'_>, value: u8) -> PyResult<Py<Self>> {
+        let cache = SIMPLE_VALUE_CACHE.get_or_init(py, || Mutex::new(HashMap::new()));
+        let mut cache = cache.lock().unwrap();
+        if let Some(existing) = cache.get(&value) {
+            return Ok(existing.clone_ref(py));
+        }
+
+        let instance = Py::new(py, CBORSimpleValue(value))?;
+        cache.insert(value, instance.clone_ref(py));
+        Ok(instance)
+    }
+}
+
 #[pymethods]
 impl CBORSimpleValue {
     #[new]
-    pub fn new(value: Bound<'_, PyInt>) -> PyResult<Self> {
+    pub fn new(py: Python<'_>, value: Bound<'_, PyInt>) -> PyResult<Py<Self>> {
         if let Ok(integer) = value.extract::<u8>()
             && !(24..32).contains(&integer)
         {
-            Ok(Self(integer))
+            Self::intern(py, integer)
         } else {
             Err(PyValueError::new_err(
                 "simple value out of range (0..23, 32..255)",
@@ -144,11 +223,29 @@ impl CBORSimpleValue {
         self.0.hash(&mut hasher);
         hasher.finish()
     }
+
+    /// Support pickling and ``copy.copy``/``copy.deepcopy``.
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let cls = py.get_type::<CBORSimpleValue>();
+        let args = PyTuple::new(py, [self.0])?;
+        PyTuple::new(py, [cls.into_any(), args.into_any()])
+    }
+
+    fn __int__(&self) -> u8 {
+        self.0
+    }
+
+    fn __index__(&self) -> u8 {
+        self.0
+    }
 }
 
 /// A hashable, immutable mapping type.
 ///
-/// The arguments to ``FrozenDict`` are processed just like those to ``dict``.
+/// The arguments to ``FrozenDict`` are processed just like those to ``dict``, including
+/// keyword arguments. It supports the full read-only ``collections.abc.Mapping`` protocol,
+/// plus ``copy()``, ``fromkeys()`` and the PEP 584 ``|``/``__ror__`` union operators (which
+/// always return a new ``FrozenDict`` rather than mutating either operand).
 #[pyclass(mapping, module = "cbor2")]
 pub struct FrozenDict {
     dict: Py<PyDict>,
@@ -158,13 +255,64 @@ pub struct FrozenDict {
 #[pymethods]
 impl FrozenDict {
     #[new]
-    #[pyo3(signature = (*args))]
-    pub fn new(args: &Bound<'_, PyTuple>) -> PyResult<Self> {
+    #[pyo3(signature = (*args, **kwargs))]
+    pub fn new(
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
         let dict_type = <PyDict as PyTypeInfo>::type_object(args.py());
-        let dict: Py<PyDict> = dict_type.call1(args)?.cast_into()?.unbind();
+        let dict: Py<PyDict> = dict_type.call(args, kwargs)?.cast_into()?.unbind();
+        Ok(Self { dict, hash: None })
+    }
+
+    /// Create a new ``FrozenDict`` with keys from ``iterable`` and values set to ``value``.
+    #[classmethod]
+    #[pyo3(signature = (iterable, value=None))]
+    fn fromkeys(
+        _cls: &Bound<'_, PyType>,
+        py: Python<'_>,
+        iterable: &Bound<'_, PyAny>,
+        value: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let dict_type = py.get_type::<PyDict>();
+        let dict: Py<PyDict> = dict_type
+            .call_method1("fromkeys", (iterable, value))?
+            .cast_into()?
+            .unbind();
         Ok(Self { dict, hash: None })
     }
 
+    /// Return a shallow copy of this ``FrozenDict``.
+    fn copy(&self, py: Python<'_>) -> Self {
+        Self {
+            dict: self.dict.bind(py).copy().unwrap().unbind(),
+            hash: self.hash,
+        }
+    }
+
+    fn __or__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let dict = self.dict.bind(py).copy()?;
+        dict.call_method1("update", (other,))?;
+        Ok(Self {
+            dict: dict.unbind(),
+            hash: None,
+        })
+    }
+
+    fn __ror__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let dict_type = <PyDict as PyTypeInfo>::type_object(py);
+        let dict: Bound<'_, PyDict> = dict_type.call1((other,))?.cast_into()?;
+        dict.call_method1("update", (self.dict.bind(py),))?;
+        Ok(Self {
+            dict: dict.unbind(),
+            hash: None,
+        })
+    }
+
+    fn __ne__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<bool> {
+        self.dict.bind(py).ne(other)
+    }
+
     fn keys<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         self.dict.bind(py).call_method0("keys")
     }
@@ -224,6 +372,13 @@ impl FrozenDict {
         }
         Ok(self.hash.unwrap())
     }
+
+    /// Support pickling and ``copy.copy``/``copy.deepcopy``.
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let cls = py.get_type::<FrozenDict>();
+        let args = PyTuple::new(py, [self.dict.bind(py).clone()])?;
+        PyTuple::new(py, [cls.into_any(), args.into_any()])
+    }
 }
 
 #[pyclass(frozen, module = "cbor2")]
@@ -238,6 +393,12 @@ impl UndefinedType {
     fn __bool__(&self) -> bool {
         false
     }
+
+    /// Reduce to the module-level ``undefined`` singleton so unpickling (and
+    /// ``copy.copy``/``copy.deepcopy``) yields the same object rather than a new instance.
+    fn __reduce__(&self) -> &str {
+        "undefined"
+    }
 }
 
 #[pyclass(frozen, module = "cbor2")]
@@ -252,4 +413,98 @@ impl BreakMarkerType {
     fn __bool__(&self) -> bool {
         true
     }
+
+    /// Reduce to the module-level ``break_marker`` singleton so unpickling (and
+    /// ``copy.copy``/``copy.deepcopy``) yields the same object rather than a new instance.
+    fn __reduce__(&self) -> &str {
+        "break_marker"
+    }
+}
+
+/// Marks the start of an array in the token stream produced by
+/// :meth:`CBORDecoder.decode_tokens`.
+///
+/// :param length: the declared number of items, or :data:`None` for an indefinite-length array
+#[pyclass(get_all, frozen, module = "cbor2")]
+pub struct ArrayStartToken {
+    pub length: Option<usize>,
+}
+
+#[pymethods]
+impl ArrayStartToken {
+    #[new]
+    pub fn new(length: Option<usize>) -> Self {
+        Self { length }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ArrayStartToken(length={:?})", self.length)
+    }
+}
+
+/// Marks the start of a map in the token stream produced by
+/// :meth:`CBORDecoder.decode_tokens`.
+///
+/// :param length: the declared number of key/value pairs, or :data:`None` for an
+///     indefinite-length map
+#[pyclass(get_all, frozen, module = "cbor2")]
+pub struct MapStartToken {
+    pub length: Option<usize>,
+}
+
+#[pymethods]
+impl MapStartToken {
+    #[new]
+    pub fn new(length: Option<usize>) -> Self {
+        Self { length }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("MapStartToken(length={:?})", self.length)
+    }
+}
+
+/// Marks the start of a semantically tagged value in the token stream produced by
+/// :meth:`CBORDecoder.decode_tokens`. Exactly one more token (a scalar, or a container and
+/// everything nested in it) follows, after which a :data:`.container_end` closes the tag.
+///
+/// :param int tag: the tag number
+#[pyclass(get_all, frozen, module = "cbor2")]
+pub struct TagStartToken {
+    pub tag: u64,
+}
+
+#[pymethods]
+impl TagStartToken {
+    #[new]
+    pub fn new(tag: u64) -> Self {
+        Self { tag }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TagStartToken(tag={})", self.tag)
+    }
+}
+
+/// Marks the end of a definite-length array or map, or of a semantically tagged value, in the
+/// token stream produced by :meth:`CBORDecoder.decode_tokens`. Indefinite-length containers are closed by
+/// :data:`break_marker` instead, matching the wire representation.
+#[pyclass(frozen, module = "cbor2")]
+pub struct ContainerEndToken;
+
+#[pymethods]
+impl ContainerEndToken {
+    fn __repr__(&self) -> &str {
+        "container_end"
+    }
+
+    fn __bool__(&self) -> bool {
+        true
+    }
+
+    /// Reduce to the module-level ``container_end`` singleton so unpickling (and
+    /// ``copy.copy``/``copy.deepcopy``) yields the same object rather than a new instance.
+    fn __reduce__(&self) -> &str {
+        "container_end"
+    }
 }
@@ -5,6 +5,61 @@ use pyo3::sync::PyOnceLock;
 import_exception!(cbor2._types, CBORDecodeError);
 import_exception!(cbor2._types, CBORDecodeValueError);
 import_exception!(cbor2._types, CBORDecodeTypeError);
+import_exception!(cbor2._types, CBORDecodeLimitError);
+import_exception!(cbor2._types, CBORDecodeEOF);
+import_exception!(cbor2._types, CBOREncodeError);
+import_exception!(cbor2._types, CBOREncodeValueError);
+import_exception!(cbor2._types, CBORWarning);
+
+/// A typed alternative to passing a stringly-typed `class_name` around: each variant already
+/// knows which CBOR exception subclass it maps to, so call sites that know their failure mode
+/// up front can build one directly and rely on `?`/`From` to turn it into a `PyErr`, instead of
+/// going through `create_cbor_error`'s runtime string match. Carries the same message/cause pair
+/// that `create_cbor_error` does.
+pub enum CborError {
+    Decode(String, Option<PyErr>),
+    DecodeValue(String, Option<PyErr>),
+    DecodeType(String, Option<PyErr>),
+    DecodeLimit(String, Option<PyErr>),
+    DecodeEof(String, Option<PyErr>),
+    Encode(String, Option<PyErr>),
+    EncodeValue(String, Option<PyErr>),
+}
+
+impl CborError {
+    fn into_py_err(self, py: Python<'_>) -> PyErr {
+        let (exc, cause) = match self {
+            CborError::Decode(msg, cause) => (CBORDecodeError::new_err(msg), cause),
+            CborError::DecodeValue(msg, cause) => (CBORDecodeValueError::new_err(msg), cause),
+            CborError::DecodeType(msg, cause) => (CBORDecodeTypeError::new_err(msg), cause),
+            CborError::DecodeLimit(msg, cause) => (CBORDecodeLimitError::new_err(msg), cause),
+            CborError::DecodeEof(msg, cause) => (CBORDecodeEOF::new_err(msg), cause),
+            CborError::Encode(msg, cause) => (CBOREncodeError::new_err(msg), cause),
+            CborError::EncodeValue(msg, cause) => (CBOREncodeValueError::new_err(msg), cause),
+        };
+        exc.set_cause(py, cause);
+        exc
+    }
+}
+
+impl From<CborError> for PyErr {
+    fn from(err: CborError) -> PyErr {
+        Python::with_gil(|py| err.into_py_err(py))
+    }
+}
+
+fn class_name_to_cbor_error(class_name: &str, msg: &str, cause: Option<PyErr>) -> CborError {
+    let msg = msg.to_string();
+    match class_name {
+        "CBORDecodeValueError" => CborError::DecodeValue(msg, cause),
+        "CBORDecodeTypeError" => CborError::DecodeType(msg, cause),
+        "CBORDecodeLimitError" => CborError::DecodeLimit(msg, cause),
+        "CBORDecodeEOF" => CborError::DecodeEof(msg, cause),
+        "CBOREncodeError" => CborError::Encode(msg, cause),
+        "CBOREncodeValueError" => CborError::EncodeValue(msg, cause),
+        _ => CborError::Decode(msg, cause),
+    }
+}
 
 
 pub struct PyImportable {
@@ -41,16 +96,7 @@ pub fn create_cbor_error(
     msg: &str,
     cause: Option<PyErr>,
 ) -> PyErr {
-    let exc = match py
-        .import("cbor2._types")
-        .and_then(|m| m.getattr(class_name))
-        .and_then(|cls| cls.call1((msg,)))
-    {
-        Err(e) => e,
-        Ok(e) => PyErr::from_value(e),
-    };
-    exc.set_cause(py, cause);
-    exc
+    class_name_to_cbor_error(class_name, msg, cause).into_py_err(py)
 }
 
 pub fn raise_cbor_error<T>(py: Python<'_>, class_name: &str, msg: &str) -> PyResult<T> {
@@ -74,3 +120,53 @@ pub fn wrap_cbor_error<T>(
 ) -> PyResult<T> {
     f().map_err(|e| create_cbor_error(py, class_name, msg, Some(e)))
 }
+
+/// Like `create_cbor_error`, but additionally sets `.offset` (the absolute byte position in
+/// the input where decoding failed) and/or `.context` (a short "while decoding X" breadcrumb)
+/// on the raised exception, and folds both into the message text, e.g. "premature end of
+/// stream at byte 42 (while decoding map key)". Either may be omitted.
+pub fn create_cbor_error_with_context(
+    py: Python<'_>,
+    class_name: &str,
+    msg: &str,
+    offset: Option<usize>,
+    context: Option<&str>,
+) -> PyErr {
+    let full_msg = match (offset, context) {
+        (Some(offset), Some(context)) => {
+            format!("{msg} at byte {offset} (while decoding {context})")
+        }
+        (Some(offset), None) => format!("{msg} at byte {offset}"),
+        (None, Some(context)) => format!("{msg} (while decoding {context})"),
+        (None, None) => msg.to_string(),
+    };
+    let exc = create_cbor_error(py, class_name, &full_msg, None);
+    if let Some(offset) = offset {
+        let _ = exc.value(py).setattr("offset", offset);
+    }
+    if let Some(context) = context {
+        let _ = exc.value(py).setattr("context", context);
+    }
+    exc
+}
+
+pub fn raise_cbor_error_with_context<T>(
+    py: Python<'_>,
+    class_name: &str,
+    msg: &str,
+    offset: Option<usize>,
+    context: Option<&str>,
+) -> PyResult<T> {
+    Err(create_cbor_error_with_context(py, class_name, msg, offset, context))
+}
+
+/// Emit a `CBORWarning` for a non-fatal but suspicious condition (a duplicate map key, a
+/// non-shortest-form length, an indefinite-length item that canonical CBOR forbids, etc.)
+/// encountered while decoding leniently. Unlike `raise_cbor_error`, this doesn't abort decoding
+/// by itself -- but if the caller has escalated warnings to errors (e.g. via
+/// `warnings.filterwarnings("error", category=CBORWarning)`), `PyErr::warn` raises instead, so
+/// propagate whatever it returns with `?` the same way as any other decode error.
+pub fn emit_cbor_warning(py: Python<'_>, msg: &str) -> PyResult<()> {
+    let category = CBORWarning::type_object(py);
+    PyErr::warn(py, category.as_any(), msg, 1)
+}